@@ -30,7 +30,8 @@ fn _run_server() -> Result<(), String> {
     server
         .get("/health", HandlerFn::new(health_endpoint))
         .unwrap();
-    server.into_fiber().start().unwrap().join().unwrap();
+    let (_handle, fiber) = server.into_fiber().unwrap();
+    fiber.start().unwrap().join().unwrap();
     Ok(())
 }
 
@@ -0,0 +1,55 @@
+use crate::echo_endpoint;
+use weaver::{
+    frontend::{extras::json::Json, handler::HandlerFn, routing::Group},
+    server::{BindParams, Server, ServerConfigBuilder, ServerHandle},
+};
+
+/// Server bound to the port picked by the system.
+pub fn ephemeral_server() -> Server {
+    let mut server = Server::new(
+        ServerConfigBuilder::default()
+            .bind(BindParams {
+                host: "127.0.0.1".into(),
+                port: 0,
+            })
+            .build()
+            .unwrap(),
+    );
+    server.post("/echo", HandlerFn::new(echo_endpoint)).unwrap();
+    server.take()
+}
+
+/// Exposes bind results and listener stats, so they could be checked from the outside.
+pub fn group(ephemeral: ServerHandle) -> Group {
+    Group::default()
+        .path("/bind")
+        .get(
+            "/ephemeral",
+            HandlerFn::new(move || {
+                let ephemeral = ephemeral.clone();
+                async move {
+                    Json(serde_json::json!({
+                        "port": ephemeral.local_addr().tcp().unwrap().port(),
+                        "accept_failures": ephemeral.accept_failures(),
+                    }))
+                }
+            }),
+        )
+        .get("/occupied", HandlerFn::new(occupied_endpoint))
+        .take()
+}
+
+/// Try to start one more server on the port of the main one.
+async fn occupied_endpoint() -> Json<serde_json::Value> {
+    let server = Server::new(
+        ServerConfigBuilder::default()
+            .bind(BindParams {
+                host: "127.0.0.1".into(),
+                port: 18989,
+            })
+            .build()
+            .unwrap(),
+    );
+    let error = server.defer().err().map(|err| err.to_string());
+    Json(serde_json::json!({ "error": error }))
+}
@@ -0,0 +1,39 @@
+use http::{header, HeaderValue, StatusCode};
+use weaver::{
+    frontend::{extras::json::Json, handler::HandlerFn, response::ResponsePart, routing::Group},
+    server::Request,
+};
+
+/// Single page application: every unknown path under the prefix is served with the index page.
+pub fn spa_group() -> Group {
+    Group::default()
+        .path("/spa")
+        .get("/api/health", HandlerFn::new(health_endpoint))
+        .fallback(HandlerFn::new(index_endpoint))
+        .take()
+}
+
+async fn health_endpoint() -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "status": "ok" }))
+}
+
+async fn index_endpoint() -> impl ResponsePart {
+    (
+        (
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("text/html; charset=utf-8"),
+        ),
+        "<html><body>index</body></html>".to_string(),
+    )
+}
+
+/// Error envelope for the requests which didn't match any route.
+pub async fn not_found_endpoint(request: Request) -> impl ResponsePart {
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({
+            "error": "not_found",
+            "path": request.uri().path(),
+        })),
+    )
+}
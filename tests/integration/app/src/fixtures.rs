@@ -0,0 +1,55 @@
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+use weaver::{
+    frontend::{extras::json::Json, handler::HandlerFn, routing::Group},
+    server::{BindParams, LocalAddr, Server, ServerConfigBuilder},
+};
+
+/// Address of the fixture listener, the port is picked by the system.
+pub fn bind() -> BindParams {
+    BindParams {
+        host: "127.0.0.1".into(),
+        port: 0,
+    }
+}
+
+/// Configuration of the fixture server, bound to the port picked by the system.
+pub fn config() -> ServerConfigBuilder {
+    let mut config = ServerConfigBuilder::default();
+    config.bind(bind());
+    config
+}
+
+/// Addresses of the fixture servers and listeners started along with the main server,
+/// by their names.
+#[derive(Default)]
+pub struct Fixtures(Rc<RefCell<BTreeMap<&'static str, String>>>);
+
+impl Fixtures {
+    pub fn defer(&self, name: &'static str, server: Server) -> Result<(), String> {
+        let handle = server
+            .defer()
+            .map_err(|err| format!("failed to start `{name}` fixture: {err}"))?;
+        self.insert(name, handle.local_addr());
+        Ok(())
+    }
+
+    /// Register the listener bound on its own, i.e. the extra listener of the main server.
+    pub fn insert(&self, name: &'static str, addr: &LocalAddr) {
+        self.0.borrow_mut().insert(name, addr.to_string());
+    }
+
+    /// Exposes the addresses fixtures are bound to, so tests could find them.
+    /// Addresses inserted after the group is built are exposed as well.
+    pub fn group(&self) -> Group {
+        let addrs = self.0.clone();
+        Group::default()
+            .get(
+                "/fixtures",
+                HandlerFn::new(move || {
+                    let addrs = addrs.borrow().clone();
+                    async move { Json(addrs) }
+                }),
+            )
+            .take()
+    }
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use weaver::frontend::{
+    extras::{form::Form, json::Json},
+    handler::HandlerFn,
+    routing::Group,
+};
+
+pub fn group() -> Group {
+    Group::default()
+        .path("/form")
+        .post("/login", HandlerFn::new(login_endpoint))
+        .get("/search", HandlerFn::new(search_endpoint))
+        .get("/settings", HandlerFn::new(settings_endpoint))
+        .take()
+}
+
+#[derive(Deserialize, Serialize)]
+struct Login {
+    username: String,
+    password: String,
+    remember: Option<bool>,
+}
+
+/// Form posted by the admin page login.
+async fn login_endpoint(Form(login): Form<Login>) -> Json<Login> {
+    Json(login)
+}
+
+#[derive(Deserialize, Serialize)]
+struct Search {
+    q: String,
+    #[serde(default)]
+    category: Vec<String>,
+}
+
+/// Form submitted with `GET` method comes in the query string.
+async fn search_endpoint(Form(search): Form<Search>) -> Json<Search> {
+    Json(search)
+}
+
+/// Legacy clients expect URL-encoded responses.
+async fn settings_endpoint() -> Form<Search> {
+    Form(Search {
+        q: "red shoes".into(),
+        category: vec!["new".into(), "sale".into()],
+    })
+}
@@ -6,14 +6,32 @@ use weaver::{
     frontend::{
         extras::json::Json,
         handler::HandlerFn,
+        middleware::MiddlewareFn,
         request::path::Path,
         response::{Extend, ResponsePart},
     },
-    server::{BindParams, Body, Request, Server, ServerConfigBuilder},
+    server::{BindParams, Body, ListenerConfigBuilder, Request, Server, ServerConfigBuilder},
 };
 
+pub mod bind;
+pub mod fallback;
+pub mod fixtures;
+pub mod form;
+pub mod limits;
 pub mod methods;
 pub mod middleware;
+pub mod multipart;
+pub mod normalization;
+pub mod params;
+pub mod protocol;
+pub mod query;
+pub mod rejection;
+pub mod rewrite;
+pub mod shutdown;
+pub mod state;
+pub mod timeouts;
+pub mod tls;
+pub mod unix;
 
 #[tarantool::proc]
 pub fn run_server(_input: String) -> Result<(), String> {
@@ -32,6 +50,13 @@ fn _run_server() -> Result<(), String> {
                 host: "127.0.0.1".into(),
                 port: 18989,
             })
+            .listener(
+                ListenerConfigBuilder::default()
+                    .bind(fixtures::bind())
+                    .name(Some("integration_internal".into()))
+                    .build()
+                    .unwrap(),
+            )
             .build()
             .unwrap(),
     );
@@ -53,9 +78,54 @@ fn _run_server() -> Result<(), String> {
 
     server.group(middleware::simple::group()).unwrap();
     server.group(middleware::layer::group()).unwrap();
+    server.group(middleware::layer::no_counter_group()).unwrap();
     server.group(methods::group()).unwrap();
+    server.group(params::group()).unwrap();
+    server.group(query::group()).unwrap();
+    server.group(form::group()).unwrap();
+    server.group(multipart::group()).unwrap();
+    server.group(rejection::group()).unwrap();
+    server.group(rejection::small_json_group()).unwrap();
+    server.group(state::group()).unwrap();
+    server.group(shutdown::group()).unwrap();
+    server.group(limits::body_group()).unwrap();
+    server.group(fallback::spa_group()).unwrap();
+    server.fallback(HandlerFn::new(fallback::not_found_endpoint));
+    server.middleware(MiddlewareFn::new(middleware::server::request_id_middleware));
+    server.pre_routing(rewrite::strip_api_prefix);
+    server.pre_routing(rewrite::method_override);
+    server.with_state(state::AppConfig {
+        greeting: "hello from the server".into(),
+    });
 
-    server.into_fiber().start().unwrap().join().unwrap();
+    let ephemeral = bind::ephemeral_server()
+        .defer()
+        .map_err(|err| err.to_string())?;
+    server.group(bind::group(ephemeral)).unwrap();
+
+    let fixtures = fixtures::Fixtures::default();
+    fixtures.defer("tls", tls::server())?;
+    fixtures.defer("mutual_tls", tls::mutual_server())?;
+    fixtures.defer("unix", unix::server())?;
+    fixtures.defer("header_read", timeouts::header_read_server())?;
+    fixtures.defer("keep_alive", timeouts::keep_alive_server())?;
+    fixtures.defer("rejecting", limits::rejecting_server())?;
+    fixtures.defer("pausing", limits::pausing_server())?;
+    fixtures.defer("http2", protocol::http2_server())?;
+    fixtures.defer("http1", protocol::http1_server())?;
+    fixtures.defer("method_not_allowed", methods::method_not_allowed_server())?;
+    fixtures.defer("redirecting", normalization::redirecting_server())?;
+    fixtures.defer(
+        "redirecting_catch_all",
+        normalization::redirecting_catch_all_server(),
+    )?;
+    fixtures.defer("matching", normalization::matching_server())?;
+    fixtures.defer("problem_details", rejection::problem_details_server())?;
+    server.group(fixtures.group()).unwrap();
+
+    let (handle, fiber) = server.into_fiber().map_err(|err| err.to_string())?;
+    fixtures.insert("internal", &handle.local_addrs()[1]);
+    fiber.start().unwrap().join().unwrap();
     Ok(())
 }
 
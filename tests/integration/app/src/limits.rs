@@ -0,0 +1,44 @@
+use crate::{echo_endpoint, fixtures};
+use std::time::Duration;
+use weaver::{
+    frontend::{extras::json::Json, handler::HandlerFn, routing::Group},
+    server::{BodyLimit, ConnectionLimit, Route, Server},
+};
+
+/// Server serving single connection at a time, the rest are rejected.
+pub fn rejecting_server() -> Server {
+    server(ConnectionLimit::reject(1, Duration::from_secs(5)))
+}
+
+/// Server serving single connection at a time, the rest are waiting.
+pub fn pausing_server() -> Server {
+    server(ConnectionLimit::pause(1))
+}
+
+fn server(limit: ConnectionLimit) -> Server {
+    let mut server = Server::new(
+        fixtures::config()
+            .connection_limit(Some(limit))
+            .build()
+            .unwrap(),
+    );
+    server.post("/echo", HandlerFn::new(echo_endpoint)).unwrap();
+    server.take()
+}
+
+/// Routes accepting small bodies only, apart from the one overriding the group limit.
+pub fn body_group() -> Group {
+    Group::default()
+        .path("/limits")
+        .body_limit(BodyLimit::Bytes(24))
+        .post("/small", HandlerFn::new(json_endpoint))
+        .route(
+            Route::new("/large", http::Method::POST).body_limit(BodyLimit::Bytes(1024)),
+            HandlerFn::new(json_endpoint),
+        )
+        .take()
+}
+
+async fn json_endpoint(Json(value): Json<serde_json::Value>) -> Json<serde_json::Value> {
+    Json(value)
+}
@@ -1,7 +1,9 @@
+use crate::fixtures;
+use http::{header, HeaderValue, StatusCode};
 use serde::Serialize;
 use weaver::{
     frontend::{extras::json::Json, handler::HandlerFn, response::ResponsePart, routing::Group},
-    server::{Request, RouteBuilder},
+    server::{AllowedMethods, Request, RouteBuilder, Server},
 };
 
 /// Test that routing works in bucket manner - even if path clashes, method resolution still works.
@@ -27,9 +29,50 @@ pub fn group() -> Group {
                 .unwrap(),
             HandlerFn::new(extension_second_endpoint),
         )
+        .get("/methods/preflight", HandlerFn::new(get_endpoint))
+        .options("/methods/preflight", HandlerFn::new(preflight_endpoint))
         .take()
 }
 
+/// Explicit `OPTIONS` handler takes precedence over the automatic one.
+async fn preflight_endpoint() -> impl ResponsePart {
+    (
+        StatusCode::NO_CONTENT,
+        [
+            (
+                header::ACCESS_CONTROL_ALLOW_ORIGIN,
+                HeaderValue::from_static("*"),
+            ),
+            (
+                header::ACCESS_CONTROL_ALLOW_METHODS,
+                HeaderValue::from_static("GET"),
+            ),
+        ],
+    )
+}
+
+/// Server answering unsupported methods with the JSON error envelope.
+pub fn method_not_allowed_server() -> Server {
+    let mut server = Server::new(fixtures::config().build().unwrap());
+    server.group(group()).unwrap();
+    server.method_not_allowed(HandlerFn::new(method_not_allowed_endpoint));
+    server.take()
+}
+
+async fn method_not_allowed_endpoint(
+    allowed: AllowedMethods,
+    request: Request,
+) -> impl ResponsePart {
+    (
+        StatusCode::METHOD_NOT_ALLOWED,
+        Json(serde_json::json!({
+            "error": "method_not_allowed",
+            "method": request.method().as_str(),
+            "allowed": allowed.iter().map(|method| method.as_str()).collect::<Vec<_>>(),
+        })),
+    )
+}
+
 async fn get_endpoint(request: Request) -> Result<impl ResponsePart, String> {
     Ok(Json(ResponseBody {
         method: request.content.method().to_string(),
@@ -2,6 +2,7 @@
 //! First middleware unsets a header if needed, second middleware would fail if the header is set.
 //! Hence, first middleware "prevents" second middleware from error.
 //! Those middlewares also does response postprocessing to demonstrate order.
+//! Counter shared by the middlewares and the handler is passed in the request extensions.
 use hyper::StatusCode;
 use std::sync::{
     atomic::{self, AtomicU64},
@@ -12,7 +13,7 @@ use weaver::{
         extras::json::Json,
         handler::HandlerFn,
         middleware::{MiddlewareFn, Next},
-        request::FromRequest,
+        request::Extension,
         response::ResponsePart,
         routing::Group,
     },
@@ -21,6 +22,7 @@ use weaver::{
 
 pub fn group() -> Group {
     Group::default()
+        .middleware(MiddlewareFn::new(counter_middleware))
         .group(
             Group::default()
                 .path("/just_second")
@@ -51,6 +53,14 @@ pub fn group() -> Group {
         .take()
 }
 
+/// Handler without the counter middleware, the counter is missing.
+pub fn no_counter_group() -> Group {
+    Group::default()
+        .path("/no_counter")
+        .post("/echo", HandlerFn::new(handler))
+        .take()
+}
+
 const MUST_BE_UNSET_HEADER: &str = "X-Must-Be-Unset";
 const WAS_SET_HEADER: &str = "X-Was-Set";
 
@@ -73,19 +83,32 @@ impl ResponsePart for Error {
     }
 }
 
+const COUNTER_HEADER: &str = "X-Counter";
+
 #[derive(Default, Clone)]
 struct TransitiveCounter(Arc<AtomicU64>);
 
-impl FromRequest for TransitiveCounter {
-    type Rejection = ();
-    async fn from_request(request: &mut Request) -> Result<Self, Self::Rejection> {
-        let counter: &mut TransitiveCounter = request.extensions_mut().get_or_insert_default();
-        Ok(Self(counter.0.clone()))
+/// Value of the counter observed by the handler.
+#[derive(Clone)]
+struct HandledCount(u64);
+
+/// Shares the counter with the inner middlewares and the handler,
+/// reports the count the handler has seen in the response header.
+async fn counter_middleware(mut request: Request, next: Next) -> Response {
+    request
+        .extensions_mut()
+        .insert(TransitiveCounter::default());
+    let mut response = next.call(request).await;
+    if let Some(HandledCount(count)) = response.extensions().get::<HandledCount>().cloned() {
+        response
+            .headers_mut()
+            .insert(COUNTER_HEADER, count.to_string().parse().unwrap());
     }
+    response
 }
 
 async fn first_middleware(
-    TransitiveCounter(counter): TransitiveCounter,
+    Extension(TransitiveCounter(counter)): Extension<TransitiveCounter>,
     mut request: Request,
     next: Next,
 ) -> Result<impl ResponsePart, Error> {
@@ -99,7 +122,7 @@ async fn first_middleware(
 }
 
 async fn second_middleware(
-    TransitiveCounter(counter): TransitiveCounter,
+    Extension(TransitiveCounter(counter)): Extension<TransitiveCounter>,
     request: Request,
     next: Next,
 ) -> Result<impl ResponsePart, Error> {
@@ -113,10 +136,11 @@ async fn second_middleware(
 }
 
 async fn handler(
-    TransitiveCounter(counter): TransitiveCounter,
+    Extension(TransitiveCounter(counter)): Extension<TransitiveCounter>,
 ) -> Result<impl ResponsePart, Error> {
-    counter.fetch_add(1, atomic::Ordering::Relaxed);
-    Ok(Json(serde_json::json!({
-        "counter": counter.load(atomic::Ordering::Relaxed)
-    })))
+    let count = counter.fetch_add(1, atomic::Ordering::Relaxed) + 1;
+    Ok((
+        Extension(HandledCount(count)),
+        Json(serde_json::json!({ "counter": count })),
+    ))
 }
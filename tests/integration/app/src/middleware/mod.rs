@@ -1,2 +1,3 @@
 pub mod layer;
+pub mod server;
 pub mod simple;
@@ -0,0 +1,25 @@
+//! Server-wide middleware, applied to every request including the unmatched ones.
+use hyper::header::HeaderValue;
+use std::sync::atomic::{AtomicU64, Ordering};
+use weaver::{
+    frontend::{middleware::Next, response::ResponsePart},
+    server::Request,
+};
+
+const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// Propagate request id to the response, generating one if the client didn't pass it.
+pub async fn request_id_middleware(request: Request, next: Next) -> impl ResponsePart {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .cloned()
+        .unwrap_or_else(|| {
+            let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+            HeaderValue::from_str(&format!("weaver-{id}")).unwrap()
+        });
+    let response = next.call(request).await;
+    (response, (REQUEST_ID_HEADER, request_id))
+}
@@ -0,0 +1,57 @@
+use serde::Serialize;
+use weaver::{
+    frontend::{
+        extras::{
+            json::Json,
+            multipart::{Multipart, MultipartError, MultipartLimitsBuilder},
+        },
+        handler::HandlerFn,
+        routing::Group,
+    },
+    server::BodyLimit,
+};
+
+pub fn group() -> Group {
+    Group::default()
+        .path("/multipart")
+        .post("/upload", HandlerFn::new(upload_endpoint))
+        .take()
+}
+
+#[derive(Serialize)]
+struct UploadedPart {
+    name: Option<String>,
+    file_name: Option<String>,
+    content_type: Option<String>,
+    size: usize,
+    chunks: usize,
+}
+
+/// Streams the uploaded parts chunk by chunk, as if writing them into a space.
+async fn upload_endpoint(
+    mut multipart: Multipart,
+) -> Result<Json<Vec<UploadedPart>>, MultipartError> {
+    multipart.limits(
+        MultipartLimitsBuilder::default()
+            .part(BodyLimit::Bytes(1024))
+            .total(BodyLimit::Bytes(4096))
+            .build()
+            .unwrap(),
+    );
+    let mut uploaded = Vec::new();
+    while let Some(mut part) = multipart.next_part().await? {
+        let mut summary = UploadedPart {
+            name: part.name().map(ToString::to_string),
+            file_name: part.file_name().map(ToString::to_string),
+            content_type: part.content_type().map(ToString::to_string),
+            size: 0,
+            chunks: 0,
+        };
+        while let Some(chunk) = part.chunk().await? {
+            summary.size += chunk.len();
+            summary.chunks += 1;
+        }
+        uploaded.push(summary);
+    }
+    Ok(Json(uploaded))
+}
@@ -0,0 +1,77 @@
+use crate::{fixtures, rewrite};
+use http::StatusCode;
+use weaver::{
+    frontend::{extras::json::Json, handler::HandlerFn, response::ResponsePart},
+    server::{PathNormalizationBuilder, Request, Server, TrailingSlash},
+};
+
+/// Server redirecting clients to the canonical paths, accepting them under the API prefix too.
+pub fn redirecting_server() -> Server {
+    let mut server = server(TrailingSlash::Redirect);
+    server.pre_routing(rewrite::strip_api_prefix);
+    server.take()
+}
+
+/// Server redirecting clients to the canonical paths, with any path being routed
+/// and duplicate slashes preserved.
+pub fn redirecting_catch_all_server() -> Server {
+    let mut server = Server::new(
+        fixtures::config()
+            .path_normalization(
+                PathNormalizationBuilder::default()
+                    .trailing_slash(TrailingSlash::Redirect)
+                    .merge_slashes(false)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap(),
+    );
+    server
+        .get("/{*path}", HandlerFn::new(path_endpoint))
+        .unwrap();
+    server.take()
+}
+
+/// Server routing paths regardless of the trailing slash.
+pub fn matching_server() -> Server {
+    server(TrailingSlash::MatchBoth)
+}
+
+fn server(trailing_slash: TrailingSlash) -> Server {
+    let mut server = Server::new(
+        fixtures::config()
+            .path_normalization(
+                PathNormalizationBuilder::default()
+                    .trailing_slash(trailing_slash)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap(),
+    );
+    server.get("/users", HandlerFn::new(path_endpoint)).unwrap();
+    server
+        .post("/users", HandlerFn::new(path_endpoint))
+        .unwrap();
+    server
+        .get("/teams/", HandlerFn::new(path_endpoint))
+        .unwrap();
+    server.fallback(HandlerFn::new(not_found_endpoint));
+    server.take()
+}
+
+/// Responds with the path the request was routed by.
+async fn path_endpoint(request: Request) -> impl ResponsePart {
+    Json(serde_json::json!({
+        "path": request.uri().path(),
+        "query": request.uri().query(),
+    }))
+}
+
+async fn not_found_endpoint(request: Request) -> impl ResponsePart {
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({ "path": request.uri().path() })),
+    )
+}
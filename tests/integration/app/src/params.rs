@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use weaver::{
+    frontend::{extras::json::Json, handler::HandlerFn, request::path::Path, routing::Group},
+    server::Request,
+};
+
+pub fn group() -> Group {
+    Group::default()
+        .path("/params")
+        .get("/raw/{name}", HandlerFn::new(raw_endpoint))
+        .get("/tuple/{id}/{name}", HandlerFn::new(tuple_endpoint))
+        .get("/struct/{id}/{kind}", HandlerFn::new(struct_endpoint))
+        .get("/single/{id}", HandlerFn::new(single_endpoint))
+        .take()
+}
+
+/// Parameters are decoded before they reach the untyped map as well.
+async fn raw_endpoint(request: Request) -> Json<serde_json::Value> {
+    Json(serde_json::json!(request.params))
+}
+
+async fn tuple_endpoint(Path((id, name)): Path<(u64, String)>) -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "id": id, "name": name }))
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Kind {
+    User,
+    Group,
+}
+
+#[derive(Deserialize, Serialize)]
+struct StructParams {
+    id: u64,
+    kind: Kind,
+}
+
+async fn struct_endpoint(Path(params): Path<StructParams>) -> Json<StructParams> {
+    Json(params)
+}
+
+async fn single_endpoint(Path(id): Path<i32>) -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "id": id }))
+}
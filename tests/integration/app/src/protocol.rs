@@ -0,0 +1,44 @@
+use crate::{echo_endpoint, fixtures};
+use weaver::{
+    frontend::handler::HandlerFn,
+    server::{Http1ConfigBuilder, Http2ConfigBuilder, Protocol, Server},
+};
+
+/// Cleartext HTTP/2 server, clients must use prior knowledge.
+pub fn http2_server() -> Server {
+    let mut server = Server::new(
+        fixtures::config()
+            .protocol(Protocol::Http2)
+            .http2(
+                Http2ConfigBuilder::default()
+                    .max_concurrent_streams(Some(16))
+                    .initial_stream_window_size(Some(1 << 20))
+                    .max_frame_size(Some(1 << 15))
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap(),
+    );
+    server.post("/echo", HandlerFn::new(echo_endpoint)).unwrap();
+    server.take()
+}
+
+/// HTTP/1 server for legacy clients, sensitive to the header names case.
+pub fn http1_server() -> Server {
+    let mut server = Server::new(
+        fixtures::config()
+            .protocol(Protocol::Http1)
+            .http1(
+                Http1ConfigBuilder::default()
+                    .title_case_headers(true)
+                    .max_buf_size(Some(64 * 1024))
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap(),
+    );
+    server.post("/echo", HandlerFn::new(echo_endpoint)).unwrap();
+    server.take()
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use weaver::frontend::{
+    extras::json::Json,
+    handler::HandlerFn,
+    request::query::{Query, RawQuery},
+    routing::Group,
+};
+
+pub fn group() -> Group {
+    Group::default()
+        .path("/query")
+        .get("/items", HandlerFn::new(items_endpoint))
+        .get("/raw", HandlerFn::new(raw_endpoint))
+        .take()
+}
+
+/// Pagination and filters of the list endpoint.
+#[derive(Deserialize, Serialize)]
+struct ItemsFilter {
+    page: u32,
+    per_page: Option<u32>,
+    #[serde(default)]
+    tag: Vec<String>,
+    search: Option<String>,
+}
+
+async fn items_endpoint(Query(filter): Query<ItemsFilter>) -> Json<ItemsFilter> {
+    Json(filter)
+}
+
+async fn raw_endpoint(RawQuery(query): RawQuery) -> Json<Option<String>> {
+    Json(query)
+}
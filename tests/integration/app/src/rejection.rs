@@ -0,0 +1,80 @@
+use crate::fixtures;
+use http::{header, HeaderValue};
+use serde::{Deserialize, Serialize};
+use weaver::{
+    frontend::{
+        extras::json::{Json, JsonConfig},
+        handler::HandlerFn,
+        request::{query::Query, rejection::Rejection},
+        routing::Group,
+    },
+    server::{Body, BodyLimit, Response, Server},
+};
+
+pub fn group() -> Group {
+    Group::default()
+        .path("/rejection")
+        .post("/users", HandlerFn::new(create_user_endpoint))
+        .get("/users", HandlerFn::new(list_users_endpoint))
+        .take()
+}
+
+/// Routes accepting only small JSON bodies.
+pub fn small_json_group() -> Group {
+    Group::default()
+        .path("/rejection/small")
+        .middleware(JsonConfig {
+            limit: BodyLimit::Bytes(32),
+        })
+        .post("/users", HandlerFn::new(create_user_endpoint))
+        .take()
+}
+
+/// Server rendering rejections in the RFC 9457 problem details format.
+pub fn problem_details_server() -> Server {
+    let mut server = Server::new(
+        fixtures::config()
+            .body_limit(BodyLimit::Bytes(64))
+            .build()
+            .unwrap(),
+    );
+    server.group(group()).unwrap();
+    server.rejection_handler(problem_details);
+    server.take()
+}
+
+#[derive(Deserialize, Serialize)]
+struct User {
+    name: String,
+    age: u8,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<u8>,
+}
+
+async fn create_user_endpoint(Json(user): Json<User>) -> Json<User> {
+    Json(user)
+}
+
+#[derive(Deserialize)]
+struct Pagination {
+    page: u32,
+}
+
+async fn list_users_endpoint(Query(pagination): Query<Pagination>) -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "page": pagination.page, "users": [] }))
+}
+
+fn problem_details(rejection: Rejection) -> Response {
+    let body = serde_json::json!({
+        "type": rejection.kind,
+        "status": rejection.status.as_u16(),
+        "detail": rejection.message,
+    });
+    let mut response = Response::new(Body::from(body.to_string()));
+    *response.status_mut() = rejection.status;
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/problem+json"),
+    );
+    response
+}
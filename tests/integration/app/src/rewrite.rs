@@ -0,0 +1,31 @@
+//! Requests rewriting before routing.
+use weaver::server::Request;
+
+const API_PREFIX: &str = "/api/v1";
+const METHOD_OVERRIDE_HEADER: &str = "X-HTTP-Method-Override";
+
+/// Strip the prefix added by the ingress, so the routes are registered without it.
+pub fn strip_api_prefix(request: &mut Request) {
+    let Some(path) = request.uri().path().strip_prefix(API_PREFIX) else {
+        return;
+    };
+    if path.is_empty() || path.starts_with('/') {
+        let path = if path.is_empty() { "/" } else { path }.to_string();
+        request.set_path(&path).unwrap();
+    }
+}
+
+/// Let clients, which are only able to send `POST`, to pass the actual method in the header.
+pub fn method_override(request: &mut Request) {
+    if request.method() != http::Method::POST {
+        return;
+    }
+    let Some(method) = request
+        .headers_mut()
+        .remove(METHOD_OVERRIDE_HEADER)
+        .and_then(|value| http::Method::from_bytes(value.as_bytes()).ok())
+    else {
+        return;
+    };
+    *request.method_mut() = method;
+}
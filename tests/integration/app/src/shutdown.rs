@@ -0,0 +1,109 @@
+use crate::{echo_endpoint, fixtures};
+use serde::Deserialize;
+use std::{cell::RefCell, rc::Rc, time::Duration};
+use tarantool::fiber;
+use weaver::{
+    frontend::{
+        extras::json::Json,
+        handler::HandlerFn,
+        request::{query::Query, state::State},
+        routing::Group,
+    },
+    server::{Server, ServerHandle},
+};
+
+/// Server being controlled by the group endpoints, started anew by every test.
+#[derive(Clone, Default)]
+struct Managed(Rc<RefCell<Option<ServerHandle>>>);
+
+impl Managed {
+    fn handle(&self) -> Result<ServerHandle, String> {
+        self.0
+            .borrow()
+            .clone()
+            .ok_or_else(|| "server is not started".into())
+    }
+}
+
+/// Controls the separate server, so its [ServerHandle] could be checked from the outside.
+pub fn group() -> Group {
+    Group::default()
+        .path("/shutdown")
+        .with_state(Managed::default())
+        .post("/start", HandlerFn::new(start_endpoint))
+        .post("/stop", HandlerFn::new(stop_endpoint))
+        .post("/initiate", HandlerFn::new(initiate_endpoint))
+        .post("/wait", HandlerFn::new(wait_endpoint))
+        .take()
+}
+
+#[derive(Deserialize)]
+struct Timeout {
+    timeout_ms: u64,
+}
+
+impl Timeout {
+    fn duration(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+}
+
+async fn start_endpoint(State(managed): State<Managed>) -> Result<Json<serde_json::Value>, String> {
+    let mut server = Server::new(fixtures::config().build().unwrap());
+    server
+        .get("/sleep", HandlerFn::new(sleep_endpoint))
+        .unwrap();
+    server.post("/echo", HandlerFn::new(echo_endpoint)).unwrap();
+    let handle = server.defer().map_err(|err| err.to_string())?;
+    let port = handle.local_addr().tcp().unwrap().port();
+    managed.0.replace(Some(handle));
+    Ok(Json(serde_json::json!({ "port": port })))
+}
+
+async fn stop_endpoint(
+    State(managed): State<Managed>,
+    Query(timeout): Query<Timeout>,
+) -> Result<Json<serde_json::Value>, String> {
+    let handle = managed.handle()?;
+    let error = handle
+        .shutdown(timeout.duration())
+        .err()
+        .map(|err| err.to_string());
+    Ok(Json(serde_json::json!({
+        "error": error,
+        "finished": handle.is_finished(),
+        "active_connections": handle.active_connections(),
+    })))
+}
+
+async fn initiate_endpoint(
+    State(managed): State<Managed>,
+) -> Result<Json<serde_json::Value>, String> {
+    let handle = managed.handle()?;
+    handle.initiate_shutdown();
+    Ok(Json(serde_json::json!({
+        "shutting_down": handle.is_shutting_down(),
+        "finished": handle.is_finished(),
+    })))
+}
+
+async fn wait_endpoint(
+    State(managed): State<Managed>,
+    Query(timeout): Query<Timeout>,
+) -> Result<Json<serde_json::Value>, String> {
+    let handle = managed.handle()?;
+    Ok(Json(serde_json::json!({
+        "finished": handle.wait(timeout.duration()),
+    })))
+}
+
+#[derive(Deserialize)]
+struct Sleep {
+    ms: u64,
+}
+
+/// In-flight request, lasting for the requested time.
+async fn sleep_endpoint(Query(sleep): Query<Sleep>) -> String {
+    fiber::sleep(Duration::from_millis(sleep.ms));
+    "done".into()
+}
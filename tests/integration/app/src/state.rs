@@ -0,0 +1,57 @@
+use std::{cell::Cell, rc::Rc};
+use weaver::{
+    frontend::{extras::json::Json, handler::HandlerFn, request::state::State, routing::Group},
+    server::Server,
+};
+
+/// Configuration shared with all the handlers of the server.
+#[derive(Clone)]
+pub struct AppConfig {
+    pub greeting: String,
+}
+
+/// Counter shared between the requests of the group.
+#[derive(Clone, Default)]
+struct Visits(Rc<Cell<u64>>);
+
+pub fn group() -> Group {
+    let mut group = Group::default()
+        .path("/state")
+        .with_state(Visits::default())
+        .get("/greeting", HandlerFn::new(greeting_endpoint))
+        .get("/visits", HandlerFn::new(visits_endpoint))
+        .get("/missing", HandlerFn::new(missing_state_endpoint))
+        .take();
+    group
+        .group(
+            Group::default()
+                .path("/override")
+                .with_state(AppConfig {
+                    greeting: "hello from the group".into(),
+                })
+                .get("/greeting", HandlerFn::new(greeting_endpoint)),
+        )
+        .unwrap();
+    group
+}
+
+async fn greeting_endpoint(State(config): State<AppConfig>) -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "greeting": config.greeting }))
+}
+
+async fn visits_endpoint(State(visits): State<Visits>) -> Json<serde_json::Value> {
+    visits.0.set(visits.0.get() + 1);
+    Json(serde_json::json!({ "visits": visits.0.get() }))
+}
+
+/// Server with handler requiring unregistered state is refused to start.
+async fn missing_state_endpoint() -> String {
+    let mut server = Server::new(Default::default());
+    server
+        .get("/visits", HandlerFn::new(visits_endpoint))
+        .unwrap();
+    match server.into_fiber() {
+        Ok(_) => "server is started".into(),
+        Err(err) => err.to_string(),
+    }
+}
@@ -0,0 +1,35 @@
+use crate::{echo_endpoint, fixtures};
+use std::time::Duration;
+use weaver::{
+    frontend::handler::HandlerFn,
+    server::{Server, TimeoutsBuilder},
+};
+
+/// Server dropping clients which are too slow to send request headers.
+pub fn header_read_server() -> Server {
+    server(
+        TimeoutsBuilder::default()
+            .header_read(Some(Duration::from_secs(1)))
+            .keep_alive(None),
+    )
+}
+
+/// Server dropping connections which are idle for too long.
+pub fn keep_alive_server() -> Server {
+    server(
+        TimeoutsBuilder::default()
+            .header_read(None)
+            .keep_alive(Some(Duration::from_secs(1))),
+    )
+}
+
+fn server(timeouts: &mut TimeoutsBuilder) -> Server {
+    let mut server = Server::new(
+        fixtures::config()
+            .timeouts(timeouts.build().unwrap())
+            .build()
+            .unwrap(),
+    );
+    server.post("/echo", HandlerFn::new(echo_endpoint)).unwrap();
+    server.take()
+}
@@ -0,0 +1,85 @@
+use crate::{echo_endpoint, fixtures};
+use std::time::Duration;
+use weaver::{
+    frontend::{
+        extras::json::Json,
+        handler::HandlerFn,
+        request::tls::{ClientCertificate, SubjectAltName},
+    },
+    server::{
+        tls::{ClientAuth, Pem, TlsConfigBuilder},
+        Server, TimeoutsBuilder,
+    },
+};
+
+const CERTS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../certs");
+
+/// Server terminating TLS on its own, certificates are loaded from files.
+/// Clients are given a second to complete the handshake.
+pub fn server() -> Server {
+    let mut server = Server::new(
+        fixtures::config()
+            .timeouts(
+                TimeoutsBuilder::default()
+                    .tls_handshake(Some(Duration::from_secs(1)))
+                    .build()
+                    .unwrap(),
+            )
+            .tls(Some(
+                TlsConfigBuilder::default()
+                    .certificate_chain(Pem::file(format!("{CERTS_DIR}/server.pem")))
+                    .private_key(Pem::file(format!("{CERTS_DIR}/server.key")))
+                    .build()
+                    .unwrap(),
+            ))
+            .build()
+            .unwrap(),
+    );
+    server.post("/echo", HandlerFn::new(echo_endpoint)).unwrap();
+    server
+        .get("/whoami", HandlerFn::new(whoami_endpoint))
+        .unwrap();
+    server.take()
+}
+
+/// Server authenticating clients with certificates signed by the test CA.
+pub fn mutual_server() -> Server {
+    let mut server = Server::new(
+        fixtures::config()
+            .tls(Some(
+                TlsConfigBuilder::default()
+                    .certificate_chain(Pem::file(format!("{CERTS_DIR}/server.pem")))
+                    .private_key(Pem::file(format!("{CERTS_DIR}/server.key")))
+                    .client_auth(Some(ClientAuth::required(Pem::file(format!(
+                        "{CERTS_DIR}/ca.pem"
+                    )))))
+                    .build()
+                    .unwrap(),
+            ))
+            .build()
+            .unwrap(),
+    );
+    server
+        .get("/whoami", HandlerFn::new(whoami_endpoint))
+        .unwrap();
+    server.take()
+}
+
+async fn whoami_endpoint(certificate: ClientCertificate) -> Json<serde_json::Value> {
+    let subject_alt_names: Vec<String> = certificate
+        .subject_alt_names
+        .iter()
+        .map(|name| match name {
+            SubjectAltName::Dns(name) => format!("DNS:{name}"),
+            SubjectAltName::Email(email) => format!("email:{email}"),
+            SubjectAltName::Uri(uri) => format!("URI:{uri}"),
+            SubjectAltName::Ip(ip) => format!("IP:{ip}"),
+        })
+        .collect();
+    Json(serde_json::json!({
+        "subject": certificate.subject,
+        "common_name": certificate.common_name,
+        "subject_alt_names": subject_alt_names,
+        "chain_length": certificate.chain.0.len(),
+    }))
+}
@@ -0,0 +1,26 @@
+use crate::echo_endpoint;
+use weaver::{
+    frontend::handler::HandlerFn,
+    server::{Server, ServerConfigBuilder, UnixBindParamsBuilder},
+};
+
+/// Server listening on the Unix domain socket, accessible by owner and group only.
+pub fn server() -> Server {
+    let mut server = Server::new(
+        ServerConfigBuilder::default()
+            .bind(
+                UnixBindParamsBuilder::default()
+                    .path(format!(
+                        "/tmp/weaver-integration-{}.sock",
+                        std::process::id()
+                    ))
+                    .permissions(Some(0o660))
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap(),
+    );
+    server.post("/echo", HandlerFn::new(echo_endpoint)).unwrap();
+    server.take()
+}
@@ -0,0 +1,29 @@
+import functools
+
+import httpx
+
+ENDPOINT = "http://localhost:18989"
+
+
+@functools.cache
+def _local_addrs() -> dict[str, str]:
+    response = httpx.get(f"{ENDPOINT}/fixtures")
+    response.raise_for_status()
+    return response.json()
+
+
+def address(name: str) -> tuple[str, int]:
+    """TCP address the fixture server is bound to."""
+    host, port = _local_addrs()[name].rsplit(":", 1)
+    return host, int(port)
+
+
+def url(name: str, scheme: str = "http") -> str:
+    """Base URL of the fixture server, `localhost` is used to match the certificates."""
+    _, port = address(name)
+    return f"{scheme}://localhost:{port}"
+
+
+def socket_path(name: str) -> str:
+    """Path of the Unix domain socket the fixture server listens on."""
+    return _local_addrs()[name].removeprefix("unix:")
@@ -0,0 +1,35 @@
+import httpx
+import pytest
+
+ENDPOINT = "http://localhost:18989"
+
+
+@pytest.mark.asyncio
+async def test_ephemeral_port():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.get("/bind/ephemeral")
+    assert response.status_code == 200, f"invalid response: {response}"
+    port = response.json()["port"]
+    assert port != 0
+
+    client = httpx.AsyncClient(base_url=f"http://localhost:{port}")
+    json_data = {"hello": "world"}
+    response = await client.post("/echo", json=json_data)
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == json_data
+
+    # Served connections are not counted as failures.
+    response = await httpx.AsyncClient(base_url=ENDPOINT).get("/bind/ephemeral")
+    assert response.json()["accept_failures"] == 0
+
+
+@pytest.mark.asyncio
+async def test_occupied_port_fails_defer():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.get("/bind/occupied")
+    assert response.status_code == 200, f"invalid response: {response}"
+    error = response.json()["error"]
+    assert error is not None
+    assert "127.0.0.1:18989" in error
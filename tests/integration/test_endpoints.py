@@ -3,6 +3,8 @@ import httpx
 import pytest
 from pydantic import BaseModel
 
+import fixture_servers
+
 ENDPOINT = "http://localhost:18989"
 
 
@@ -20,6 +22,19 @@ async def test_echo_endpoint():
     assert response.json() == json_data
 
 
+@pytest.mark.asyncio
+async def test_additional_listener_shares_routes():
+    client = httpx.AsyncClient(base_url=fixture_servers.url("internal"))
+
+    json_data = {"hello": "world"}
+    response = await client.post("/echo", json=json_data)
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == json_data
+
+    response = await client.get("/path/1/content/2/3")
+    assert response.status_code == 200, f"invalid response: {response}"
+
+
 @pytest.mark.asyncio
 async def test_json_endpoint():
     client = httpx.AsyncClient(base_url=ENDPOINT)
@@ -126,6 +141,7 @@ async def test_middleware_chaining():
     response = await client.post("/just_second/echo", json={"hello": "world"})
     assert response.status_code == 200, f"invalid response: {response}"
     assert response.json() == {"counter": 2}
+    assert response.headers["x-counter"] == "2"
 
     response = await client.post(
         "/just_second/echo",
@@ -139,6 +155,7 @@ async def test_middleware_chaining():
     assert response.status_code == 200, f"invalid response: {response}"
     assert response.json() == {"counter": 3}
     assert response.headers["x-was-set"] == "false"
+    assert response.headers["x-counter"] == "3"
 
     response = await client.post(
         "/combined/echo",
@@ -154,6 +171,16 @@ async def test_middleware_chaining():
     assert response.json() == {"counter": 3}
 
 
+@pytest.mark.asyncio
+async def test_missing_extension():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.post("/no_counter/echo", json={"hello": "world"})
+    assert response.status_code == 500, f"invalid response: {response}"
+    assert response.json()["error"] == "missing_extension"
+    assert "TransitiveCounter" in response.json()["message"]
+
+
 @pytest.mark.asyncio
 async def test_methods_endpoint():
     client = httpx.AsyncClient(base_url=ENDPOINT)
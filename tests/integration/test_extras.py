@@ -0,0 +1,118 @@
+import httpx
+import pytest
+
+ENDPOINT = "http://localhost:18989"
+
+
+@pytest.mark.asyncio
+async def test_form_body():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.post(
+        "/form/login", data={"username": "admin", "password": "p@ss word"}
+    )
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == {
+        "username": "admin",
+        "password": "p@ss word",
+        "remember": None,
+    }
+
+
+@pytest.mark.asyncio
+async def test_form_rejection():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.post(
+        "/form/login", json={"username": "admin", "password": "secret"}
+    )
+    assert response.status_code == 415, f"invalid response: {response}"
+
+    response = await client.post("/form/login", data={"username": "admin"})
+    assert response.status_code == 422, f"invalid response: {response}"
+    assert response.json() == {
+        "error": "invalid_form_data",
+        "message": "failed to deserialize form: missing field `password`",
+    }
+
+
+@pytest.mark.asyncio
+async def test_form_query():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.get(
+        "/form/search", params=[("q", "red shoes"), ("category", "a"), ("category", "b")]
+    )
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == {"q": "red shoes", "category": ["a", "b"]}
+
+
+@pytest.mark.asyncio
+async def test_form_response():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.get("/form/settings")
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.headers["content-type"] == "application/x-www-form-urlencoded"
+    assert response.text == "q=red+shoes&category=new&category=sale"
+
+
+@pytest.mark.asyncio
+async def test_multipart_upload():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    csv = b"id,name\n1,alice\n2,bob\n"
+    response = await client.post(
+        "/multipart/upload",
+        data={"space": "users"},
+        files={"import": ("users.csv", csv, "text/csv")},
+    )
+    assert response.status_code == 200, f"invalid response: {response}"
+    parts = response.json()
+    assert [part["name"] for part in parts] == ["space", "import"]
+    assert parts[0]["file_name"] is None
+    assert parts[0]["size"] == len("users")
+    assert parts[1]["file_name"] == "users.csv"
+    assert parts[1]["content_type"] == "text/csv"
+    assert parts[1]["size"] == len(csv)
+
+
+@pytest.mark.asyncio
+async def test_multipart_limits():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.post(
+        "/multipart/upload",
+        files={"attachment": ("big.bin", b"x" * 2048, "application/octet-stream")},
+    )
+    assert response.status_code == 413, f"invalid response: {response}"
+    assert response.json() == {
+        "error": "payload_too_large",
+        "message": "multipart part `attachment` exceeds the limit of 1024 bytes",
+    }
+
+    files = [
+        (f"file{i}", (f"file{i}.bin", b"x" * 1000, "application/octet-stream"))
+        for i in range(5)
+    ]
+    response = await client.post("/multipart/upload", files=files)
+    assert response.status_code == 413, f"invalid response: {response}"
+    assert response.json() == {
+        "error": "payload_too_large",
+        "message": "multipart parts exceed the limit of 4096 bytes",
+    }
+
+
+@pytest.mark.asyncio
+async def test_multipart_rejection():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.post("/multipart/upload", json={"file": "content"})
+    assert response.status_code == 415, f"invalid response: {response}"
+
+    response = await client.post(
+        "/multipart/upload",
+        content=b"--boundary\r\n",
+        headers={"content-type": "multipart/form-data"},
+    )
+    assert response.status_code == 400, f"invalid response: {response}"
@@ -0,0 +1,175 @@
+import asyncio
+import select
+import socket
+import time
+
+import httpx
+import pytest
+
+import fixture_servers
+
+REQUEST = (
+    b"POST /echo HTTP/1.1\r\n"
+    b"Host: localhost\r\n"
+    b"Content-Length: 5\r\n"
+    b"\r\n"
+    b"hello"
+)
+
+
+def occupy_connection(address) -> socket.socket:
+    """Open keep-alive connection and make sure the server serves it."""
+    sock = socket.create_connection(address)
+    sock.settimeout(5)
+    sock.sendall(REQUEST)
+    response = b""
+    while not response.endswith(b"hello"):
+        response += sock.recv(4096)
+    assert response.startswith(b"HTTP/1.1 200 OK"), response
+    return sock
+
+
+def open_burst(address, amount: int) -> list[socket.socket]:
+    """Open connections at once, so they are queued together before being accepted."""
+    socks = [socket.create_connection(address) for _ in range(amount)]
+    for sock in socks:
+        sock.settimeout(5)
+        sock.sendall(REQUEST)
+    return socks
+
+
+def status_line(sock: socket.socket) -> bytes:
+    response = b""
+    while b"\r\n" not in response and (chunk := sock.recv(4096)):
+        response += chunk
+    return response.split(b"\r\n", 1)[0]
+
+
+def answered(socks: list[socket.socket], timeout: float) -> list[socket.socket]:
+    """Connections which got some response within the timeout."""
+    time.sleep(timeout)
+    ready, _, _ = select.select(socks, [], [], 0)
+    return ready
+
+
+def close_all(socks: list[socket.socket]):
+    for sock in socks:
+        sock.close()
+    # Give the server a moment to notice the closed connections.
+    time.sleep(0.5)
+
+
+def test_connection_burst_over_limit_is_rejected():
+    socks = open_burst(fixture_servers.address("rejecting"), 5)
+    try:
+        statuses = sorted(status_line(sock) for sock in socks)
+    finally:
+        close_all(socks)
+    rejected = [b"HTTP/1.1 503 Service Unavailable"] * 4
+    assert statuses == [b"HTTP/1.1 200 OK", *rejected], statuses
+
+
+def test_connection_burst_over_limit_waits():
+    socks = open_burst(fixture_servers.address("pausing"), 3)
+    try:
+        served = answered(socks, timeout=1)
+        assert len(served) == 1, "connections over limit must wait"
+
+        # Next connection is served once the slot is released.
+        served[0].close()
+        socks.remove(served[0])
+        served = answered(socks, timeout=1)
+        assert len(served) == 1, "single connection must be admitted"
+        assert status_line(served[0]) == b"HTTP/1.1 200 OK"
+    finally:
+        close_all(socks)
+
+
+@pytest.mark.asyncio
+async def test_connection_over_limit_is_rejected():
+    with occupy_connection(fixture_servers.address("rejecting")):
+        client = httpx.AsyncClient(base_url=fixture_servers.url("rejecting"))
+        response = await client.post("/echo", content="hello")
+        assert response.status_code == 503, f"invalid response: {response}"
+        assert response.headers["retry-after"] == "5"
+
+    # Give the server a moment to notice the closed connection.
+    await asyncio.sleep(0.5)
+    async with httpx.AsyncClient(base_url=fixture_servers.url("rejecting")) as client:
+        response = await client.post("/echo", content="hello")
+        assert response.status_code == 200, f"invalid response: {response}"
+
+
+def test_connection_over_limit_is_answered_before_request():
+    address = fixture_servers.address("rejecting")
+    with occupy_connection(address):
+        with socket.create_connection(address) as sock:
+            sock.settimeout(5)
+            response = b""
+            while chunk := sock.recv(4096):
+                response += chunk
+    assert response == (
+        b"HTTP/1.1 503 Service Unavailable\r\n"
+        b"Retry-After: 5\r\n"
+        b"Connection: close\r\n"
+        b"Content-Length: 0\r\n"
+        b"\r\n"
+    ), response
+    # Give the server a moment to notice the closed connection.
+    time.sleep(0.5)
+
+
+@pytest.mark.asyncio
+async def test_connection_over_limit_waits():
+    sock = occupy_connection(fixture_servers.address("pausing"))
+    client = httpx.AsyncClient(base_url=fixture_servers.url("pausing"))
+    with pytest.raises(httpx.ReadTimeout):
+        await client.post("/echo", content="hello", timeout=1)
+
+    sock.close()
+    response = await client.post("/echo", content="hello", timeout=5)
+    assert response.status_code == 200, f"invalid response: {response}"
+
+
+@pytest.mark.asyncio
+async def test_body_over_declared_limit():
+    client = httpx.AsyncClient(base_url="http://localhost:18989")
+
+    response = await client.post("/limits/small", json={"hello": "world"})
+    assert response.status_code == 200, f"invalid response: {response}"
+
+    response = await client.post("/limits/small", json={"hello": "x" * 32})
+    assert response.status_code == 413, f"invalid response: {response}"
+    assert response.json() == {
+        "error": "payload_too_large",
+        "message": "request body exceeds the limit of 24 bytes",
+    }
+
+
+@pytest.mark.asyncio
+async def test_streamed_body_over_limit():
+    client = httpx.AsyncClient(base_url="http://localhost:18989")
+
+    async def chunks():
+        yield b'{"hello": "'
+        yield b"x" * 32
+        yield b'"}'
+
+    # Body without content length is checked while being read.
+    response = await client.post(
+        "/limits/small",
+        content=chunks(),
+        headers={"content-type": "application/json"},
+    )
+    assert response.status_code == 413, f"invalid response: {response}"
+    assert response.json()["error"] == "payload_too_large"
+
+
+@pytest.mark.asyncio
+async def test_route_overrides_group_body_limit():
+    client = httpx.AsyncClient(base_url="http://localhost:18989")
+
+    json_data = {"hello": "x" * 32}
+    response = await client.post("/limits/large", json=json_data)
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == json_data
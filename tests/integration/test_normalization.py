@@ -0,0 +1,116 @@
+import socket
+
+import httpx
+import pytest
+
+import fixture_servers
+
+ENDPOINT = "http://localhost:18989"
+
+
+def raw_get(address, path):
+    with socket.create_connection(address) as sock:
+        sock.settimeout(5)
+        sock.sendall(
+            f"GET {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n".encode()
+        )
+        response = b""
+        while chunk := sock.recv(4096):
+            response += chunk
+    return response
+
+
+@pytest.mark.asyncio
+async def test_duplicate_slashes_are_merged():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.get("//spa//api/health")
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == {"status": "ok"}
+
+    # Trailing slash is significant by default.
+    response = await client.get("/spa/api/health/")
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.text == "<html><body>index</body></html>"
+
+
+def test_dot_segments_are_resolved():
+    response = raw_get(
+        fixture_servers.address("matching"), "/teams/./../users/.?page=2"
+    )
+    assert response.startswith(b"HTTP/1.1 200 OK"), response
+    assert b'"path":"/users"' in response, response
+    assert b'"query":"page=2"' in response, response
+
+    response = raw_get(fixture_servers.address("matching"), "/../../users")
+    assert response.startswith(b"HTTP/1.1 200 OK"), response
+
+
+@pytest.mark.asyncio
+async def test_trailing_slash_redirect():
+    client = httpx.AsyncClient(base_url=fixture_servers.url("redirecting"))
+
+    response = await client.get("/users/?page=2")
+    assert response.status_code == 308, f"invalid response: {response}"
+    assert response.headers["location"] == "/users?page=2"
+
+    response = await client.post("/users/")
+    assert response.status_code == 308, f"invalid response: {response}"
+    assert response.headers["location"] == "/users"
+
+    response = await client.get("/teams")
+    assert response.status_code == 308, f"invalid response: {response}"
+    assert response.headers["location"] == "/teams/"
+
+    response = await client.get("//users")
+    assert response.status_code == 308, f"invalid response: {response}"
+    assert response.headers["location"] == "/users"
+
+    response = await client.get("/users")
+    assert response.status_code == 200, f"invalid response: {response}"
+
+    response = await client.get("/missing/")
+    assert response.status_code == 404, f"invalid response: {response}"
+    assert response.json() == {"path": "/missing/"}
+
+
+@pytest.mark.asyncio
+async def test_trailing_slash_redirect_keeps_rewritten_prefix():
+    client = httpx.AsyncClient(base_url=fixture_servers.url("redirecting"))
+
+    # Prefix is stripped by the pre-routing hook, but the client still needs it.
+    response = await client.get("/api/v1/users/?page=2")
+    assert response.status_code == 308, f"invalid response: {response}"
+    assert response.headers["location"] == "/api/v1/users?page=2"
+
+    response = await client.get("/api/v1/teams")
+    assert response.status_code == 308, f"invalid response: {response}"
+    assert response.headers["location"] == "/api/v1/teams/"
+
+    response = await client.get("/api/v1/users")
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == {"path": "/users", "query": None}
+
+
+def test_redirect_stays_on_host():
+    address = fixture_servers.address("redirecting_catch_all")
+
+    # Dot segment is resolved into `//evil.com`, which is not a local path.
+    response = raw_get(address, "/..//evil.com")
+    assert response.startswith(b"HTTP/1.1 308 Permanent Redirect"), response
+    assert b"\r\nlocation: /evil.com\r\n" in response, response
+
+
+@pytest.mark.asyncio
+async def test_trailing_slash_match_both():
+    client = httpx.AsyncClient(base_url=fixture_servers.url("matching"))
+
+    for path, routed in [
+        ("/users", "/users"),
+        ("/users/", "/users"),
+        ("/teams", "/teams/"),
+        ("/teams/", "/teams/"),
+    ]:
+        response = await client.get(path)
+        assert response.status_code == 200, f"invalid response for {path}: {response}"
+        assert response.json() == {"path": routed, "query": None}
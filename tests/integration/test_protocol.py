@@ -0,0 +1,45 @@
+import socket
+
+import httpx
+import pytest
+
+import fixture_servers
+
+
+@pytest.mark.asyncio
+async def test_http2_prior_knowledge():
+    client = httpx.AsyncClient(
+        base_url=fixture_servers.url("http2"), http1=False, http2=True
+    )
+
+    json_data = {"hello": "world"}
+    response = await client.post("/echo", json=json_data)
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.http_version == "HTTP/2"
+    assert response.json() == json_data
+
+
+@pytest.mark.asyncio
+async def test_http2_only_rejects_http1():
+    client = httpx.AsyncClient(base_url=fixture_servers.url("http2"))
+
+    with pytest.raises(httpx.TransportError):
+        await client.post("/echo", json={"hello": "world"})
+
+
+def test_http1_title_case_headers():
+    with socket.create_connection(fixture_servers.address("http1")) as sock:
+        sock.settimeout(5)
+        sock.sendall(
+            b"POST /echo HTTP/1.1\r\n"
+            b"Host: localhost\r\n"
+            b"Content-Length: 5\r\n"
+            b"\r\n"
+            b"hello"
+        )
+        response = b""
+        while not response.endswith(b"hello"):
+            response += sock.recv(4096)
+
+    assert response.startswith(b"HTTP/1.1 200 OK"), response
+    assert b"\r\nContent-Length: 5\r\n" in response, response
@@ -0,0 +1,130 @@
+import httpx
+import pytest
+
+import fixture_servers
+
+ENDPOINT = "http://localhost:18989"
+
+
+@pytest.mark.asyncio
+async def test_json_rejection():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.post("/rejection/users", json={"name": "Ann", "age": 30})
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == {"name": "Ann", "age": 30}
+
+    response = await client.post(
+        "/rejection/users",
+        content=b'{"name": "Ann",',
+        headers={"content-type": "application/json"},
+    )
+    assert response.status_code == 400, f"invalid response: {response}"
+    assert response.headers["content-type"] == "application/json"
+    assert response.json()["error"] == "invalid_json"
+
+    response = await client.post(
+        "/rejection/users",
+        content=b'{"name": "Ann", "age": 3} x',
+        headers={"content-type": "application/json"},
+    )
+    assert response.status_code == 400, f"invalid response: {response}"
+    assert response.json()["details"] == {"line": 1, "column": 27}
+
+
+@pytest.mark.asyncio
+async def test_json_data_rejection():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.post(
+        "/rejection/users",
+        content=b'{"name": "Ann", "age": 300}',
+        headers={"content-type": "application/json"},
+    )
+    assert response.status_code == 422, f"invalid response: {response}"
+    assert response.json() == {
+        "error": "invalid_json_data",
+        "message": (
+            "failed to deserialize JSON at `age`: "
+            "invalid value: integer `300`, expected u8 at line 1 column 26"
+        ),
+        "details": {"path": "age", "line": 1, "column": 26},
+    }
+
+    response = await client.post(
+        "/rejection/users",
+        content=b'{"name": "Ann", "age": 3, "tags": [1, "x"]}',
+        headers={"content-type": "application/json"},
+    )
+    assert response.status_code == 422, f"invalid response: {response}"
+    assert 'invalid type: string "x"' in response.json()["message"]
+    assert response.json()["details"] == {"path": "tags[1]", "line": 1, "column": 41}
+
+
+@pytest.mark.asyncio
+async def test_json_content_type():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.post("/rejection/users", data={"name": "Ann", "age": 30})
+    assert response.status_code == 415, f"invalid response: {response}"
+    assert response.json()["error"] == "unsupported_media_type"
+
+    response = await client.post(
+        "/rejection/users", content=b'{"name": "Ann", "age": 30}'
+    )
+    assert response.status_code == 415, f"invalid response: {response}"
+
+    response = await client.post(
+        "/rejection/users",
+        content=b'{"name": "Ann", "age": 30}',
+        headers={"content-type": "application/merge-patch+json; charset=utf-8"},
+    )
+    assert response.status_code == 200, f"invalid response: {response}"
+
+
+@pytest.mark.asyncio
+async def test_json_limit():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.post(
+        "/rejection/small/users", json={"name": "Ann", "age": 30}
+    )
+    assert response.status_code == 200, f"invalid response: {response}"
+
+    response = await client.post(
+        "/rejection/small/users", json={"name": "Ann" * 16, "age": 30}
+    )
+    assert response.status_code == 413, f"invalid response: {response}"
+    assert response.json()["error"] == "payload_too_large"
+
+
+@pytest.mark.asyncio
+async def test_custom_rejection_rendering():
+    client = httpx.AsyncClient(base_url=fixture_servers.url("problem_details"))
+
+    response = await client.post("/rejection/users", json={"name": "Ann"})
+    assert response.status_code == 422, f"invalid response: {response}"
+    assert response.headers["content-type"] == "application/problem+json"
+    body = response.json()
+    assert body["type"] == "invalid_json_data"
+    assert body["status"] == 422
+    assert "missing field `age`" in body["detail"]
+
+    response = await client.post("/rejection/users", data={"name": "Ann"})
+    assert response.status_code == 415, f"invalid response: {response}"
+    assert response.json()["type"] == "unsupported_media_type"
+
+    response = await client.get("/rejection/users?page=first")
+    assert response.status_code == 400, f"invalid response: {response}"
+    assert response.headers["content-type"] == "application/problem+json"
+    assert response.json()["type"] == "invalid_query"
+
+    # Declared length is checked before the handler, but rendered all the same.
+    response = await client.post("/rejection/users", json={"name": "Ann" * 32})
+    assert response.status_code == 413, f"invalid response: {response}"
+    assert response.headers["content-type"] == "application/problem+json"
+    assert response.json()["type"] == "payload_too_large"
+
+    response = await client.get("/rejection/users?page=2")
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == {"page": 2, "users": []}
@@ -0,0 +1,254 @@
+import httpx
+import pytest
+
+import fixture_servers
+
+ENDPOINT = "http://localhost:18989"
+METHODS_ALLOWED = {
+    "GET",
+    "HEAD",
+    "POST",
+    "OPTIONS",
+    "VOROJBA",
+    "ONE_HELL_LONG_VOROJBA_EXTENSION",
+}
+
+
+def allow_header(response):
+    return {method.strip() for method in response.headers["allow"].split(",")}
+
+
+@pytest.mark.asyncio
+async def test_server_fallback():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.get("/definitely/missing")
+    assert response.status_code == 404, f"invalid response: {response}"
+    assert response.json() == {"error": "not_found", "path": "/definitely/missing"}
+
+
+@pytest.mark.asyncio
+async def test_group_fallback():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.get("/spa/api/health")
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == {"status": "ok"}
+
+    for path in ["/spa", "/spa/", "/spa/settings/profile"]:
+        response = await client.get(path)
+        assert response.status_code == 200, f"invalid response for {path}: {response}"
+        assert response.headers["content-type"] == "text/html; charset=utf-8"
+        assert response.text == "<html><body>index</body></html>"
+
+    # Prefix is matched by whole segments.
+    response = await client.get("/spactacular")
+    assert response.status_code == 404, f"invalid response: {response}"
+
+
+@pytest.mark.asyncio
+async def test_method_not_allowed():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.delete("/methods")
+    assert response.status_code == 405, f"invalid response: {response}"
+    assert allow_header(response) == METHODS_ALLOWED
+    assert response.text == "405 Method Not Allowed"
+
+
+@pytest.mark.asyncio
+async def test_custom_method_not_allowed():
+    client = httpx.AsyncClient(base_url=fixture_servers.url("method_not_allowed"))
+
+    response = await client.get("/methods")
+    assert response.status_code == 200, f"invalid response: {response}"
+
+    response = await client.put("/methods")
+    assert response.status_code == 405, f"invalid response: {response}"
+    assert allow_header(response) == METHODS_ALLOWED
+    body = response.json()
+    assert body["error"] == "method_not_allowed"
+    assert body["method"] == "PUT"
+    assert set(body["allowed"]) == METHODS_ALLOWED
+
+
+@pytest.mark.asyncio
+async def test_automatic_head():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    get_response = await client.get("/spa/api/health")
+    assert get_response.status_code == 200, f"invalid response: {get_response}"
+
+    response = await client.head("/spa/api/health")
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.content == b""
+    assert response.headers["content-length"] == str(len(get_response.content))
+    assert response.headers["content-type"] == get_response.headers["content-type"]
+
+
+@pytest.mark.asyncio
+async def test_automatic_options():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.options("/methods")
+    assert response.status_code == 204, f"invalid response: {response}"
+    assert allow_header(response) == METHODS_ALLOWED
+    assert response.content == b""
+
+    # Routes without `GET` are not served for `HEAD`.
+    response = await client.options("/json")
+    assert response.status_code == 204, f"invalid response: {response}"
+    assert allow_header(response) == {"POST", "OPTIONS"}
+
+
+@pytest.mark.asyncio
+async def test_explicit_options():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.options("/methods/preflight")
+    assert response.status_code == 204, f"invalid response: {response}"
+    assert response.headers["access-control-allow-origin"] == "*"
+    assert response.headers["access-control-allow-methods"] == "GET"
+    assert "allow" not in response.headers
+
+
+@pytest.mark.asyncio
+async def test_server_middleware():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    requests = [
+        ("GET", "/spa/api/health", 200),
+        ("POST", "/counter_protected/echo", 200),
+        ("GET", "/definitely/missing", 404),
+        ("DELETE", "/methods", 405),
+        ("OPTIONS", "/methods", 204),
+    ]
+    for method, path, status in requests:
+        response = await client.request(
+            method, path, headers={"X-Request-Id": f"{method}-{path}"}
+        )
+        assert response.status_code == status, f"invalid response for {path}: {response}"
+        assert response.headers["x-request-id"] == f"{method}-{path}"
+
+    response = await client.get("/definitely/missing")
+    assert response.headers["x-request-id"].startswith("weaver-")
+
+
+@pytest.mark.asyncio
+async def test_pre_routing_rewrite():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.get("/api/v1/spa/api/health?verbose=1")
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == {"status": "ok"}
+
+    # Prefix is matched by whole segments.
+    response = await client.get("/api/v1spa/api/health")
+    assert response.status_code == 404, f"invalid response: {response}"
+
+    response = await client.post(
+        "/api/v1/methods", headers={"X-HTTP-Method-Override": "VOROJBA"}
+    )
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == {
+        "method": "VOROJBA",
+        "endpoint": "extension_first_endpoint",
+    }
+
+
+@pytest.mark.asyncio
+async def test_path_params_are_decoded():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.get("/params/raw/a%20b%2Fc")
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == {"name": "a b/c"}
+
+    response = await client.get("/params/raw/%FF")
+    assert response.status_code == 400, f"invalid response: {response}"
+    assert response.json()["error"] == "invalid_path_params"
+
+
+@pytest.mark.asyncio
+async def test_typed_path_params():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.get("/params/tuple/42/report%201.pdf")
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == {"id": 42, "name": "report 1.pdf"}
+
+    response = await client.get("/params/struct/7/group")
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == {"id": 7, "kind": "group"}
+
+    response = await client.get("/params/single/-5")
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == {"id": -5}
+
+
+@pytest.mark.asyncio
+async def test_typed_path_params_rejection():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.get("/params/tuple/abc/report")
+    assert response.status_code == 400, f"invalid response: {response}"
+    assert response.json() == {
+        "error": "invalid_path_params",
+        "message": (
+            "invalid path parameters: failed to parse path parameter `id` "
+            'value "abc" as u64: invalid digit found in string'
+        ),
+    }
+
+    response = await client.get("/params/struct/7/robot")
+    assert response.status_code == 400, f"invalid response: {response}"
+    assert "unknown variant `robot`" in response.json()["message"]
+
+
+@pytest.mark.asyncio
+async def test_query():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.get(
+        "/query/items?page=2&tag=new&tag=on%20sale&search=red+shoes"
+    )
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == {
+        "page": 2,
+        "per_page": None,
+        "tag": ["new", "on sale"],
+        "search": "red shoes",
+    }
+
+    response = await client.get("/query/items?page=1&per_page=50")
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == {"page": 1, "per_page": 50, "tag": [], "search": None}
+
+
+@pytest.mark.asyncio
+async def test_query_rejection():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.get("/query/items?per_page=50")
+    assert response.status_code == 400, f"invalid response: {response}"
+    assert response.json() == {
+        "error": "invalid_query",
+        "message": "invalid query: missing field `page`",
+    }
+
+    response = await client.get("/query/items?page=first")
+    assert response.status_code == 400, f"invalid response: {response}"
+    assert response.json()["message"].startswith("invalid query: ")
+
+
+@pytest.mark.asyncio
+async def test_raw_query():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.get("/query/raw?a=1&b=%20")
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == "a=1&b=%20"
+
+    response = await client.get("/query/raw")
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() is None
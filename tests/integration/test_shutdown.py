@@ -0,0 +1,129 @@
+import asyncio
+import socket
+import time
+
+import httpx
+import pytest
+
+ENDPOINT = "http://localhost:18989"
+
+
+async def start_server(control: httpx.AsyncClient) -> int:
+    response = await control.post("/shutdown/start")
+    assert response.status_code == 200, f"invalid response: {response}"
+    return response.json()["port"]
+
+
+@pytest.mark.asyncio
+async def test_shutdown_closes_idle_connections():
+    async with httpx.AsyncClient(base_url=ENDPOINT, timeout=10) as control:
+        port = await start_server(control)
+        async with httpx.AsyncClient(base_url=f"http://localhost:{port}") as client:
+            # Keep-alive connection stays idle once the request is answered.
+            response = await client.get("/sleep", params={"ms": 0})
+            assert response.status_code == 200, f"invalid response: {response}"
+
+            start = time.monotonic()
+            response = await control.post("/shutdown/stop", params={"timeout_ms": 5000})
+            assert response.status_code == 200, f"invalid response: {response}"
+            assert response.json() == {
+                "error": None,
+                "finished": True,
+                "active_connections": 0,
+            }
+            assert time.monotonic() - start < 1
+
+            with pytest.raises(httpx.TransportError):
+                await client.get("/sleep", params={"ms": 0})
+
+
+@pytest.mark.asyncio
+async def test_shutdown_drains_in_flight_requests():
+    async with httpx.AsyncClient(base_url=ENDPOINT, timeout=10) as control:
+        port = await start_server(control)
+        async with httpx.AsyncClient(base_url=f"http://localhost:{port}") as client:
+            in_flight = asyncio.create_task(client.get("/sleep", params={"ms": 1000}))
+            await asyncio.sleep(0.2)
+
+            response = await control.post("/shutdown/stop", params={"timeout_ms": 5000})
+            assert response.status_code == 200, f"invalid response: {response}"
+            assert response.json()["error"] is None
+
+            response = await in_flight
+            assert response.status_code == 200, f"invalid response: {response}"
+            assert response.text == "done"
+
+
+@pytest.mark.asyncio
+async def test_wait_for_shutdown():
+    async with httpx.AsyncClient(base_url=ENDPOINT, timeout=10) as control:
+        port = await start_server(control)
+        async with httpx.AsyncClient(base_url=f"http://localhost:{port}") as client:
+            in_flight = asyncio.create_task(client.get("/sleep", params={"ms": 1000}))
+            await asyncio.sleep(0.2)
+
+            response = await control.post("/shutdown/initiate")
+            assert response.status_code == 200, f"invalid response: {response}"
+            assert response.json() == {"shutting_down": True, "finished": False}
+
+            response = await control.post("/shutdown/wait", params={"timeout_ms": 100})
+            assert response.json() == {"finished": False}
+
+            response = await control.post("/shutdown/wait", params={"timeout_ms": 5000})
+            assert response.json() == {"finished": True}
+
+            response = await in_flight
+            assert response.status_code == 200, f"invalid response: {response}"
+
+
+@pytest.mark.asyncio
+async def test_shutdown_aborts_connections_after_deadline():
+    async with httpx.AsyncClient(base_url=ENDPOINT, timeout=10) as control:
+        port = await start_server(control)
+        with socket.create_connection(("127.0.0.1", port)) as sock:
+            # Handler waits for the rest of the body, which is never sent.
+            sock.sendall(
+                b"POST /echo HTTP/1.1\r\n"
+                b"Host: localhost\r\n"
+                b"Content-Length: 10\r\n"
+                b"\r\n"
+                b"hello"
+            )
+            await asyncio.sleep(0.2)
+
+            start = time.monotonic()
+            response = await control.post("/shutdown/stop", params={"timeout_ms": 300})
+            assert response.status_code == 200, f"invalid response: {response}"
+            assert response.json() == {
+                "error": "server shutdown deadline exceeded, 1 connections were aborted",
+                "finished": True,
+                "active_connections": 0,
+            }
+            assert time.monotonic() - start < 2
+
+            sock.settimeout(5)
+            assert sock.recv(4096) == b""
+
+
+@pytest.mark.asyncio
+async def test_shutdown_does_not_wait_for_running_handlers():
+    async with httpx.AsyncClient(base_url=ENDPOINT, timeout=10) as control:
+        port = await start_server(control)
+        async with httpx.AsyncClient(base_url=f"http://localhost:{port}") as client:
+            in_flight = asyncio.create_task(client.get("/sleep", params={"ms": 3000}))
+            await asyncio.sleep(0.2)
+
+            start = time.monotonic()
+            response = await control.post("/shutdown/stop", params={"timeout_ms": 300})
+            assert response.status_code == 200, f"invalid response: {response}"
+            assert response.json() == {
+                "error": "server shutdown deadline exceeded, 1 connections were aborted",
+                "finished": False,
+                "active_connections": 1,
+            }
+            assert time.monotonic() - start < 2
+
+            # Connection is closed once the handler returns.
+            response = await control.post("/shutdown/wait", params={"timeout_ms": 5000})
+            assert response.json() == {"finished": True}
+            await asyncio.gather(in_flight, return_exceptions=True)
@@ -0,0 +1,47 @@
+import httpx
+import pytest
+
+ENDPOINT = "http://localhost:18989"
+
+
+@pytest.mark.asyncio
+async def test_server_state():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.get("/state/greeting")
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == {"greeting": "hello from the server"}
+
+
+@pytest.mark.asyncio
+async def test_group_state_overrides_server_one():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.get("/state/override/greeting")
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == {"greeting": "hello from the group"}
+
+
+@pytest.mark.asyncio
+async def test_group_state_is_shared():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.get("/state/visits")
+    assert response.status_code == 200, f"invalid response: {response}"
+    visits = response.json()["visits"]
+
+    response = await client.get("/state/visits")
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == {"visits": visits + 1}
+
+
+@pytest.mark.asyncio
+async def test_missing_state_is_rejected_on_start():
+    client = httpx.AsyncClient(base_url=ENDPOINT)
+
+    response = await client.get("/state/missing")
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.text == (
+        "state `integration_suite_app::state::Visits` required by "
+        "route GET /visits is not registered"
+    )
@@ -0,0 +1,38 @@
+import socket
+import time
+
+import fixture_servers
+
+REQUEST = (
+    b"POST /echo HTTP/1.1\r\n"
+    b"Host: localhost\r\n"
+    b"Content-Length: 5\r\n"
+    b"\r\n"
+    b"hello"
+)
+
+
+def read_until_closed(sock: socket.socket, timeout: float) -> bytes:
+    sock.settimeout(timeout)
+    content = b""
+    while chunk := sock.recv(4096):
+        content += chunk
+    return content
+
+
+def test_slow_headers_are_dropped():
+    with socket.create_connection(fixture_servers.address("header_read")) as sock:
+        sock.sendall(b"POST /echo HTTP/1.1\r\nHost: localhost\r\n")
+        start = time.monotonic()
+        read_until_closed(sock, timeout=5)
+        assert time.monotonic() - start < 3
+
+
+def test_idle_connection_is_closed():
+    with socket.create_connection(fixture_servers.address("keep_alive")) as sock:
+        sock.sendall(REQUEST)
+        start = time.monotonic()
+        response = read_until_closed(sock, timeout=5)
+        assert response.startswith(b"HTTP/1.1 200 OK"), response
+        assert response.endswith(b"hello"), response
+        assert time.monotonic() - start < 3
@@ -0,0 +1,89 @@
+import pathlib
+import socket
+import ssl
+import time
+
+import httpx
+import pytest
+
+import fixture_servers
+
+CERTS_DIR = pathlib.Path(__file__).parent / "certs"
+
+
+def server_verification() -> ssl.SSLContext:
+    return ssl.create_default_context(cafile=CERTS_DIR / "ca.pem")
+
+
+@pytest.mark.asyncio
+async def test_tls_echo_endpoint():
+    client = httpx.AsyncClient(
+        base_url=fixture_servers.url("tls", scheme="https"),
+        verify=server_verification(),
+    )
+
+    json_data = {"hello": "world"}
+    response = await client.post("/echo", json=json_data)
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == json_data
+
+
+@pytest.mark.asyncio
+async def test_tls_rejects_cleartext():
+    client = httpx.AsyncClient(base_url=fixture_servers.url("tls"))
+
+    with pytest.raises(httpx.TransportError):
+        await client.post("/echo", json={"hello": "world"})
+
+
+@pytest.mark.asyncio
+async def test_mtls_client_certificate():
+    context = server_verification()
+    context.load_cert_chain(CERTS_DIR / "client.pem", CERTS_DIR / "client.key")
+    client = httpx.AsyncClient(
+        base_url=fixture_servers.url("mutual_tls", scheme="https"), verify=context
+    )
+
+    response = await client.get("/whoami")
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == {
+        "subject": "O=weaver, CN=test-client",
+        "common_name": "test-client",
+        "subject_alt_names": [
+            "DNS:client.internal",
+            "URI:spiffe://weaver/test-client",
+        ],
+        "chain_length": 1,
+    }
+
+
+@pytest.mark.asyncio
+async def test_mtls_rejects_anonymous_client():
+    client = httpx.AsyncClient(
+        base_url=fixture_servers.url("mutual_tls", scheme="https"),
+        verify=server_verification(),
+    )
+
+    with pytest.raises(httpx.TransportError):
+        await client.get("/whoami")
+
+
+@pytest.mark.asyncio
+async def test_client_certificate_is_required_by_extractor():
+    # Plain TLS server doesn't ask for client certificate at all.
+    client = httpx.AsyncClient(
+        base_url=fixture_servers.url("tls", scheme="https"),
+        verify=server_verification(),
+    )
+
+    response = await client.get("/whoami")
+    assert response.status_code == 401, f"invalid response: {response}"
+
+
+def test_idle_handshake_is_dropped():
+    # Client connects, but never starts the handshake.
+    with socket.create_connection(fixture_servers.address("tls")) as sock:
+        sock.settimeout(5)
+        start = time.monotonic()
+        assert sock.recv(4096) == b""
+        assert time.monotonic() - start < 3
@@ -0,0 +1,24 @@
+import os
+import stat
+
+import httpx
+import pytest
+
+import fixture_servers
+
+
+@pytest.mark.asyncio
+async def test_unix_echo_endpoint():
+    transport = httpx.AsyncHTTPTransport(uds=fixture_servers.socket_path("unix"))
+    client = httpx.AsyncClient(base_url="http://localhost", transport=transport)
+
+    json_data = {"hello": "world"}
+    response = await client.post("/echo", json=json_data)
+    assert response.status_code == 200, f"invalid response: {response}"
+    assert response.json() == json_data
+
+
+def test_unix_socket_permissions():
+    mode = os.stat(fixture_servers.socket_path("unix")).st_mode
+    assert stat.S_ISSOCK(mode)
+    assert stat.S_IMODE(mode) == 0o660
@@ -0,0 +1,116 @@
+use super::super::{
+    request::{rejection::Rejection, FromRequest},
+    response::ResponsePart,
+};
+use crate::{
+    frontend::response::error::InternalError,
+    server::{BodyError, Request, Response},
+};
+use http::{header, HeaderValue, Method, StatusCode};
+use http_body_util::BodyExt as _;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// URL-encoded form, i.e. submitted by HTML `<form>`.
+///
+/// Extracted from the query string for `GET` and `HEAD` requests, from the
+/// `application/x-www-form-urlencoded` body otherwise.
+/// Serialized into the body with the same content type when used as a response part.
+pub struct Form<T>(pub T);
+
+impl<T> From<T> for Form<T> {
+    fn from(data: T) -> Self {
+        Self(data)
+    }
+}
+
+impl<T: DeserializeOwned> FromRequest for Form<T> {
+    type Rejection = FormRejection;
+
+    async fn from_request(request: &mut Request) -> Result<Self, Self::Rejection> {
+        if request.method() == Method::GET || request.method() == Method::HEAD {
+            let query = request.uri().query().unwrap_or_default();
+            return serde_html_form::from_str(query)
+                .map(Self)
+                .map_err(FormRejection::Deserialize);
+        }
+
+        if !has_form_content_type(request) {
+            return Err(FormRejection::UnsupportedContentType);
+        }
+        let body = request
+            .body_mut()
+            .collect()
+            .await
+            .map_err(FormRejection::Body)?
+            .to_bytes();
+        serde_html_form::from_bytes(&body)
+            .map(Self)
+            .map_err(FormRejection::Deserialize)
+    }
+}
+
+fn has_form_content_type(request: &Request) -> bool {
+    request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<mime::Mime>().ok())
+        .is_some_and(|mime| mime.essence_str() == mime::APPLICATION_WWW_FORM_URLENCODED.as_ref())
+}
+
+/// Request can't be extracted as a form.
+#[derive(Debug)]
+pub enum FormRejection {
+    /// Content type is not `application/x-www-form-urlencoded`, rendered as 415.
+    UnsupportedContentType,
+    /// Body can't be collected, rendered as 413 if it exceeds the limit, 400 otherwise.
+    Body(BodyError),
+    /// Form can't be deserialized into the requested type, rendered as 422.
+    Deserialize(serde::de::value::Error),
+}
+
+impl From<FormRejection> for Rejection {
+    fn from(rejection: FormRejection) -> Self {
+        match rejection {
+            FormRejection::UnsupportedContentType => Self::new(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "unsupported_media_type",
+                format!(
+                    "expected request with `{}: {}`",
+                    header::CONTENT_TYPE,
+                    mime::APPLICATION_WWW_FORM_URLENCODED
+                ),
+            ),
+            FormRejection::Body(err) => err.into(),
+            FormRejection::Deserialize(err) => Self::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "invalid_form_data",
+                format!("failed to deserialize form: {err}"),
+            ),
+        }
+    }
+}
+
+impl ResponsePart for FormRejection {
+    async fn apply(self, response: &mut Response) {
+        Rejection::from(self).apply(response).await;
+    }
+}
+
+impl<T: Serialize> ResponsePart for Form<T> {
+    async fn apply(self, response: &mut Response) {
+        serde_html_form::to_string(&self.0)
+            .map(|body| {
+                (
+                    (
+                        header::CONTENT_TYPE,
+                        HeaderValue::from_static(mime::APPLICATION_WWW_FORM_URLENCODED.as_ref()),
+                    ),
+                    body,
+                )
+            })
+            .map_err(InternalError::from)
+            .apply(response)
+            .await;
+    }
+}
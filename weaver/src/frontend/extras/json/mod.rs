@@ -1,13 +1,55 @@
-use super::super::{request::FromRequest, response::ResponsePart};
+use super::super::{
+    middleware::{Middleware, Next},
+    request::{
+        rejection::{Rejection, RejectionDetails},
+        FromRequest,
+    },
+    response::ResponsePart,
+};
 use crate::{
     frontend::response::error::InternalError,
-    server::{Request, Response},
+    server::{BodyError, BodyLimit, Request, Response},
 };
-use bytes::{BufMut as _, BytesMut};
-use http::{header, HeaderValue};
+use bytes::{BufMut as _, Bytes, BytesMut};
+use derive_builder::Builder;
+use http::{header, HeaderValue, StatusCode};
 use http_body_util::BodyExt as _;
 use serde::de::DeserializeOwned;
+use serde_json::error::Category;
 
+/// JSON body of the request or response.
+///
+/// Extracted only from the requests with `application/json` or `application/*+json`
+/// content type, rejects with 415 otherwise. Body size is bounded by [JsonConfig::limit]
+/// in addition to the route body limit. Malformed JSON is rejected with 400, JSON not
+/// matching `T` with 422, both report the position of the error.
+///
+/// Example:
+///
+/// ```rust
+/// use weaver::frontend::extras::json::{Json, JsonConfig};
+/// use weaver::frontend::handler::HandlerFn;
+/// use weaver::frontend::routing::Group;
+/// use weaver::server::BodyLimit;
+///
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// struct User {
+///     name: String,
+/// }
+///
+/// fn group() -> Group {
+///     Group::default()
+///         .middleware(JsonConfig {
+///             limit: BodyLimit::Bytes(64 * 1024),
+///         })
+///         .post("/users", HandlerFn::new(create_user))
+///         .take()
+/// }
+///
+/// async fn create_user(Json(user): Json<User>) -> Json<User> {
+///     Json(user)
+/// }
+/// ```
 pub struct Json<T>(pub T);
 
 impl<T> From<T> for Json<T> {
@@ -16,22 +58,164 @@ impl<T> From<T> for Json<T> {
     }
 }
 
+/// Configuration of the [Json] extractor.
+///
+/// Used as a middleware: applies to the requests of the server or group it's registered on.
+#[derive(Debug, Clone, Copy, Builder)]
+#[builder(default)]
+pub struct JsonConfig {
+    /// Maximum size of the JSON body, 2 MiB by default.
+    /// Route body limit still applies if it's lower.
+    pub limit: BodyLimit,
+}
+
+impl Default for JsonConfig {
+    fn default() -> Self {
+        Self {
+            limit: BodyLimit::DEFAULT,
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Middleware for JsonConfig {
+    async fn process(&self, mut request: Request, next: Next) -> Response {
+        request.extensions_mut().insert(*self);
+        next.call(request).await
+    }
+}
+
 impl<T: DeserializeOwned> FromRequest for Json<T> {
-    type Rejection = String;
+    type Rejection = JsonRejection;
 
     async fn from_request(request: &mut Request) -> Result<Self, Self::Rejection> {
-        let body = request
-            .body_mut()
-            .collect()
+        if !has_json_content_type(request) {
+            return Err(JsonRejection::UnsupportedContentType);
+        }
+        let config = request
+            .extensions()
+            .get::<JsonConfig>()
+            .copied()
+            .unwrap_or_default();
+        let body = collect_body(request, config.limit)
             .await
-            .map_err(|err| format!("failed to collect body: {}", err))?
-            .to_bytes();
-        let data = serde_json::from_slice(&body)
-            .map_err(|e| format!("failed to deserialize data: {}", e))?;
+            .map_err(JsonRejection::Body)?;
+
+        let mut deserializer = serde_json::Deserializer::from_slice(&body);
+        let data = serde_path_to_error::deserialize(&mut deserializer).map_err(|err| {
+            let path = err.path().to_string();
+            let err = err.into_inner();
+            match err.classify() {
+                Category::Data => JsonRejection::Data { path, error: err },
+                Category::Io | Category::Syntax | Category::Eof => JsonRejection::Syntax(err),
+            }
+        })?;
+        // Trailing characters after the value.
+        deserializer.end().map_err(JsonRejection::Syntax)?;
         Ok(Self(data))
     }
 }
 
+fn has_json_content_type(request: &Request) -> bool {
+    request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<mime::Mime>().ok())
+        .is_some_and(|mime| {
+            mime.type_() == mime::APPLICATION
+                && (mime.subtype() == mime::JSON || mime.suffix() == Some(mime::JSON))
+        })
+}
+
+/// Collect the body, failing as soon as it exceeds the limit.
+async fn collect_body(request: &mut Request, limit: BodyLimit) -> Result<Bytes, BodyError> {
+    let exceeded = |length: u64| match limit {
+        BodyLimit::Bytes(bytes) if limit.is_exceeded_by(length) => {
+            Err(BodyError::LengthLimit { limit: bytes })
+        }
+        _ => Ok(()),
+    };
+
+    let declared_length = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    if let Some(length) = declared_length {
+        exceeded(length)?;
+    }
+
+    let mut body = BytesMut::new();
+    while let Some(frame) = request.body_mut().frame().await {
+        if let Ok(data) = frame?.into_data() {
+            body.extend_from_slice(&data);
+            exceeded(body.len() as u64)?;
+        }
+    }
+    Ok(body.freeze())
+}
+
+/// Request body can't be extracted as JSON.
+#[derive(Debug)]
+pub enum JsonRejection {
+    /// Content type is not `application/json` nor `application/*+json`, rendered as 415.
+    UnsupportedContentType,
+    /// Body can't be collected, rendered as 413 if it exceeds the limit, 400 otherwise.
+    Body(BodyError),
+    /// Body is not a valid JSON, rendered as 400.
+    Syntax(serde_json::Error),
+    /// Body is a valid JSON, but doesn't match the requested type, rendered as 422.
+    Data {
+        /// Path to the invalid value, i.e. `items[0].price`, `.` for the root.
+        path: String,
+        error: serde_json::Error,
+    },
+}
+
+impl From<JsonRejection> for Rejection {
+    fn from(rejection: JsonRejection) -> Self {
+        match rejection {
+            JsonRejection::UnsupportedContentType => Self::new(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "unsupported_media_type",
+                format!(
+                    "expected request with `{}: {}`",
+                    header::CONTENT_TYPE,
+                    mime::APPLICATION_JSON
+                ),
+            ),
+            JsonRejection::Body(err) => err.into(),
+            JsonRejection::Syntax(err) => Self::new(
+                StatusCode::BAD_REQUEST,
+                "invalid_json",
+                format!("failed to parse JSON: {err}"),
+            )
+            .with_details(RejectionDetails {
+                line: Some(err.line()),
+                column: Some(err.column()),
+                ..Default::default()
+            }),
+            JsonRejection::Data { path, error } => Self::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "invalid_json_data",
+                format!("failed to deserialize JSON at `{path}`: {error}"),
+            )
+            .with_details(RejectionDetails {
+                path: Some(path),
+                line: Some(error.line()),
+                column: Some(error.column()),
+            }),
+        }
+    }
+}
+
+impl ResponsePart for JsonRejection {
+    async fn apply(self, response: &mut Response) {
+        Rejection::from(self).apply(response).await;
+    }
+}
+
 impl<T: serde::Serialize> ResponsePart for Json<T> {
     async fn apply(self, response: &mut Response) {
         // Extracted into separate fn so it's only compiled once for all T.
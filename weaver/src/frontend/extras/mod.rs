@@ -1,2 +1,6 @@
+#[cfg(feature = "form")]
+pub mod form;
 #[cfg(feature = "json")]
 pub mod json;
+#[cfg(feature = "multipart")]
+pub mod multipart;
@@ -0,0 +1,275 @@
+use super::super::{
+    request::{rejection::Rejection, FromRequest},
+    response::ResponsePart,
+};
+use crate::server::{BodyError, BodyLimit, Request, Response};
+use bytes::{Bytes, BytesMut};
+use derive_builder::Builder;
+use http::{header, HeaderMap, StatusCode};
+use http_body_util::BodyExt as _;
+
+/// Streaming `multipart/form-data` body, i.e. file uploads.
+///
+/// Parts are read one by one and their content is streamed in chunks as it arrives,
+/// so the upload is never buffered as a whole. Request body limit applies to the whole body,
+/// [MultipartLimits] narrow it down for the parts.
+///
+/// Example:
+///
+/// ```rust
+/// use weaver::frontend::extras::multipart::{Multipart, MultipartError, MultipartLimits};
+/// use weaver::server::BodyLimit;
+///
+/// async fn handler(mut multipart: Multipart) -> Result<String, MultipartError> {
+///     multipart.limits(MultipartLimits {
+///         part: BodyLimit::Bytes(1024 * 1024),
+///         ..Default::default()
+///     });
+///     let mut total = 0;
+///     while let Some(mut part) = multipart.next_part().await? {
+///         while let Some(chunk) = part.chunk().await? {
+///             total += chunk.len();
+///         }
+///     }
+///     Ok(format!("received {total} bytes"))
+/// }
+/// ```
+pub struct Multipart {
+    inner: multer::Multipart<'static>,
+    limits: MultipartLimits,
+    /// Content bytes of all the parts read so far.
+    read: u64,
+}
+
+/// Limits of the parts content, unlimited by default.
+#[derive(Debug, Clone, Copy, Builder)]
+#[builder(default)]
+pub struct MultipartLimits {
+    /// Maximum size of a single part content.
+    pub part: BodyLimit,
+    /// Maximum size of all the parts content together.
+    pub total: BodyLimit,
+}
+
+impl Default for MultipartLimits {
+    fn default() -> Self {
+        Self {
+            part: BodyLimit::Unlimited,
+            total: BodyLimit::Unlimited,
+        }
+    }
+}
+
+impl Multipart {
+    /// Set the limits of the parts which are not read yet.
+    pub fn limits(&mut self, limits: MultipartLimits) -> &mut Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Next part of the body, `None` if there are no more parts.
+    /// Unread content of the previous part is skipped.
+    pub async fn next_part(&mut self) -> Result<Option<Part<'_>>, MultipartError> {
+        let Some(inner) = self
+            .inner
+            .next_field()
+            .await
+            .map_err(MultipartError::from)?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(Part {
+            inner,
+            limits: self.limits,
+            total_read: &mut self.read,
+            read: 0,
+        }))
+    }
+}
+
+impl FromRequest for Multipart {
+    type Rejection = MultipartRejection;
+
+    async fn from_request(request: &mut Request) -> Result<Self, Self::Rejection> {
+        let content_type = request
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .ok_or(MultipartRejection::UnsupportedContentType)?;
+        let boundary = multer::parse_boundary(content_type).map_err(|err| match err {
+            multer::Error::NoBoundary => MultipartRejection::MissingBoundary,
+            _ => MultipartRejection::UnsupportedContentType,
+        })?;
+        let body = std::mem::take(request.body_mut());
+        Ok(Self {
+            inner: multer::Multipart::new(body.into_data_stream(), boundary),
+            limits: MultipartLimits::default(),
+            read: 0,
+        })
+    }
+}
+
+/// Single part of the multipart body.
+pub struct Part<'a> {
+    inner: multer::Field<'static>,
+    limits: MultipartLimits,
+    total_read: &'a mut u64,
+    /// Content bytes of this part read so far.
+    read: u64,
+}
+
+impl Part<'_> {
+    /// Name of the form field.
+    pub fn name(&self) -> Option<&str> {
+        self.inner.name()
+    }
+
+    /// Name of the uploaded file, if the part is a file.
+    pub fn file_name(&self) -> Option<&str> {
+        self.inner.file_name()
+    }
+
+    pub fn content_type(&self) -> Option<&mime::Mime> {
+        self.inner.content_type()
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        self.inner.headers()
+    }
+
+    /// Next chunk of the part content, `None` if the part is read completely.
+    pub async fn chunk(&mut self) -> Result<Option<Bytes>, MultipartError> {
+        let Some(chunk) = self.inner.chunk().await.map_err(MultipartError::from)? else {
+            return Ok(None);
+        };
+        self.read += chunk.len() as u64;
+        *self.total_read += chunk.len() as u64;
+        if let BodyLimit::Bytes(limit) = self.limits.part {
+            if self.limits.part.is_exceeded_by(self.read) {
+                return Err(MultipartError::PartTooLarge {
+                    name: self.name().map(ToString::to_string),
+                    limit,
+                });
+            }
+        }
+        if let BodyLimit::Bytes(limit) = self.limits.total {
+            if self.limits.total.is_exceeded_by(*self.total_read) {
+                return Err(MultipartError::TooLarge { limit });
+            }
+        }
+        Ok(Some(chunk))
+    }
+
+    /// Read the whole part content.
+    pub async fn bytes(mut self) -> Result<Bytes, MultipartError> {
+        let mut content = BytesMut::new();
+        while let Some(chunk) = self.chunk().await? {
+            content.extend_from_slice(&chunk);
+        }
+        Ok(content.freeze())
+    }
+
+    /// Read the whole part content as UTF-8 text.
+    pub async fn text(self) -> Result<String, MultipartError> {
+        let content = self.bytes().await?;
+        String::from_utf8(content.into()).map_err(|_| MultipartError::InvalidUtf8)
+    }
+}
+
+/// Request is not a multipart one.
+#[derive(Debug)]
+pub enum MultipartRejection {
+    /// Content type is not `multipart/form-data`.
+    UnsupportedContentType,
+    /// Content type has no boundary parameter.
+    MissingBoundary,
+}
+
+impl From<MultipartRejection> for Rejection {
+    fn from(rejection: MultipartRejection) -> Self {
+        match rejection {
+            MultipartRejection::UnsupportedContentType => Self::new(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "unsupported_media_type",
+                format!(
+                    "expected request with `{}: {}`",
+                    header::CONTENT_TYPE,
+                    mime::MULTIPART_FORM_DATA
+                ),
+            ),
+            MultipartRejection::MissingBoundary => Self::new(
+                StatusCode::BAD_REQUEST,
+                "missing_multipart_boundary",
+                "multipart boundary is missing",
+            ),
+        }
+    }
+}
+
+impl ResponsePart for MultipartRejection {
+    async fn apply(self, response: &mut Response) {
+        Rejection::from(self).apply(response).await;
+    }
+}
+
+/// Failure of reading the multipart body.
+///
+/// Could be returned from the handler as is, it's rendered with the matching status.
+#[derive(thiserror::Error, Debug)]
+pub enum MultipartError {
+    #[error("multipart part `{}` exceeds the limit of {limit} bytes", name.as_deref().unwrap_or_default())]
+    PartTooLarge { name: Option<String>, limit: usize },
+    #[error("multipart parts exceed the limit of {limit} bytes")]
+    TooLarge { limit: usize },
+    #[error(transparent)]
+    Body(BodyError),
+    #[error("malformed multipart body: {0}")]
+    Malformed(String),
+    #[error("multipart part is not valid UTF-8")]
+    InvalidUtf8,
+}
+
+impl MultipartError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::PartTooLarge { .. }
+            | Self::TooLarge { .. }
+            | Self::Body(BodyError::LengthLimit { .. }) => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::Body(BodyError::Transport(_)) | Self::Malformed(_) | Self::InvalidUtf8 => {
+                StatusCode::BAD_REQUEST
+            }
+        }
+    }
+}
+
+impl From<multer::Error> for MultipartError {
+    fn from(err: multer::Error) -> Self {
+        match err {
+            multer::Error::StreamReadFailed(err) => match err.downcast::<BodyError>() {
+                Ok(err) => Self::Body(*err),
+                Err(err) => Self::Malformed(err.to_string()),
+            },
+            err => Self::Malformed(err.to_string()),
+        }
+    }
+}
+
+impl From<MultipartError> for Rejection {
+    fn from(err: MultipartError) -> Self {
+        let kind = match err {
+            MultipartError::Body(err) => return err.into(),
+            MultipartError::PartTooLarge { .. } | MultipartError::TooLarge { .. } => {
+                "payload_too_large"
+            }
+            MultipartError::Malformed(_) => "malformed_multipart",
+            MultipartError::InvalidUtf8 => "invalid_utf8",
+        };
+        Self::new(err.status(), kind, err.to_string())
+    }
+}
+
+impl ResponsePart for MultipartError {
+    async fn apply(self, response: &mut Response) {
+        Rejection::from(self).apply(response).await;
+    }
+}
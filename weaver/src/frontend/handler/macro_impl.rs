@@ -42,6 +42,13 @@ macro_rules! impl_request_handler {
                         parts.apply(&mut response).await;
                         response
                     }
+
+                    #[allow(unused_mut)]
+                    fn required_states(&self) -> Vec<$crate::server::StateType> {
+                        let mut states = Vec::new();
+                        $( states.extend(<$arg as FromRequest>::required_state()); )*
+                        states
+                    }
                 }
             }
         }
@@ -88,6 +95,13 @@ macro_rules! impl_request_handler {
                         parts.apply(&mut response).await;
                         response
                     }
+
+                    #[allow(unused_mut)]
+                    fn required_states(&self) -> Vec<$crate::server::StateType> {
+                        let mut states = Vec::new();
+                        $( states.extend(<$arg as FromRequest>::required_state()); )*
+                        states
+                    }
                 }
             }
         }
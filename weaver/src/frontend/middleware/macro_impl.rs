@@ -45,6 +45,13 @@ macro_rules! impl_middleware {
                         parts.apply(&mut response).await;
                         response
                     }
+
+                    #[allow(unused_mut)]
+                    fn required_states(&self) -> Vec<$crate::server::StateType> {
+                        let mut states = Vec::new();
+                        $( states.extend(<$arg as FromRequest>::required_state()); )*
+                        states
+                    }
                 }
             }
         }
@@ -91,6 +98,13 @@ macro_rules! impl_middleware {
                         parts.apply(&mut response).await;
                         response
                     }
+
+                    #[allow(unused_mut)]
+                    fn required_states(&self) -> Vec<$crate::server::StateType> {
+                        let mut states = Vec::new();
+                        $( states.extend(<$arg as FromRequest>::required_state()); )*
+                        states
+                    }
                 }
             }
         }
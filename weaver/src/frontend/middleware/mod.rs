@@ -1,4 +1,4 @@
-use crate::server::{Request, RequestHandler, Response, SharedRequestHandler};
+use crate::server::{Request, RequestHandler, Response, SharedRequestHandler, StateType};
 use std::{marker::PhantomData, rc::Rc};
 
 mod macro_impl;
@@ -6,6 +6,11 @@ mod macro_impl;
 #[async_trait::async_trait(?Send)]
 pub trait Middleware {
     async fn process(&self, request: Request, next: Next) -> Response;
+
+    /// States the middleware extracts, checked to be registered before the server starts.
+    fn required_states(&self) -> Vec<StateType> {
+        Vec::new()
+    }
 }
 
 /// Middleware takes [super::response::ResponsePart]
@@ -80,6 +85,12 @@ impl SharedMiddleware {
                     .process(request, self.next.clone())
                     .await
             }
+
+            fn required_states(&self) -> Vec<StateType> {
+                let mut states = self.middleware.as_middleware().required_states();
+                states.extend(self.next.handler.as_handler().required_states());
+                states
+            }
         }
 
         Next {
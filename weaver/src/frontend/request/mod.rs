@@ -1,9 +1,15 @@
 use super::response::ResponsePart;
-use crate::server::Request;
-use http::{HeaderMap, HeaderValue};
+use crate::server::{AllowedMethods, Request, Response, StateType};
+use http::{HeaderMap, HeaderValue, StatusCode};
+use rejection::Rejection;
 use std::future::Future;
 
 pub mod path;
+pub mod query;
+pub mod rejection;
+pub mod state;
+#[cfg(feature = "tls")]
+pub mod tls;
 
 pub trait FromRequest {
     type Rejection: ResponsePart;
@@ -11,6 +17,12 @@ pub trait FromRequest {
     fn from_request(request: &mut Request) -> impl Future<Output = Result<Self, Self::Rejection>>
     where
         Self: Sized;
+
+    /// State the extractor requires to be registered on the server or group,
+    /// see [state::State].
+    fn required_state() -> Option<StateType> {
+        None
+    }
 }
 
 impl FromRequest for HeaderMap<HeaderValue> {
@@ -48,3 +60,84 @@ impl FromRequest for Extensions {
         Ok(Self(std::mem::take(request.extensions_mut())))
     }
 }
+
+/// Extracts a single typed value from the request extensions, i.e. inserted by a middleware.
+///
+/// Value is cloned, so it stays available to the other extractors and the handler.
+/// Rejects with 500 if there is no value of the type.
+///
+/// Used as a response part, inserts the value into the response extensions.
+///
+/// Example:
+///
+/// ```rust
+/// use weaver::frontend::request::Extension;
+/// use weaver::frontend::middleware::Next;
+/// use weaver::server::Request;
+///
+/// #[derive(Clone)]
+/// struct User {
+///     name: String,
+/// }
+///
+/// async fn auth_middleware(mut request: Request, next: Next) -> weaver::server::Response {
+///     request.extensions_mut().insert(User { name: "admin".into() });
+///     next.call(request).await
+/// }
+///
+/// async fn handler(Extension(user): Extension<User>) -> String {
+///     format!("hello, {}", user.name)
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Extension<T>(pub T);
+
+impl<T: Clone + Send + Sync + 'static> FromRequest for Extension<T> {
+    type Rejection = Rejection;
+
+    async fn from_request(request: &mut Request) -> Result<Self, Self::Rejection> {
+        request
+            .extensions()
+            .get::<T>()
+            .cloned()
+            .map(Self)
+            .ok_or_else(|| {
+                Rejection::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "missing_extension",
+                    format!(
+                        "request extension `{}` is missing",
+                        std::any::type_name::<T>()
+                    ),
+                )
+            })
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> ResponsePart for Extension<T> {
+    async fn apply(self, response: &mut Response) {
+        response.extensions_mut().insert(self.0);
+    }
+}
+
+/// Extracts methods registered for the matched path.
+///
+/// Available only in the handler set with [crate::server::Server::method_not_allowed],
+/// rejects with 500 otherwise.
+impl FromRequest for AllowedMethods {
+    type Rejection = Rejection;
+
+    async fn from_request(request: &mut Request) -> Result<Self, Self::Rejection> {
+        request
+            .extensions()
+            .get::<AllowedMethods>()
+            .cloned()
+            .ok_or_else(|| {
+                Rejection::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "missing_allowed_methods",
+                    "allowed methods are available in the method not allowed handler only",
+                )
+            })
+    }
+}
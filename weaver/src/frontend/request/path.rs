@@ -1,8 +1,21 @@
-use super::{FromRequest, Request};
+use super::{rejection::Rejection, FromRequest, Request};
+use crate::{
+    frontend::response::ResponsePart,
+    server::{PathParams, Response},
+};
+use http::StatusCode;
+use serde::de::DeserializeOwned;
 use std::collections::HashMap;
 
-/// Extracts path parameters from the request.
-/// It consumes the request's path parameters, so future invocations will result in an empty map.
+mod de;
+
+pub use de::PathDeserializationError;
+
+/// Extracts percent-decoded path parameters from the request.
+///
+/// Parameters are deserialized into `T`: a map or a struct by parameter names,
+/// a tuple in the order they appear in the path, or a primitive if there is a single one.
+/// Rejects with 400 if parameters can't be deserialized.
 ///
 /// Example:
 ///
@@ -11,22 +24,68 @@ use std::collections::HashMap;
 /// use weaver::frontend::handler::HandlerFn;
 /// use weaver::server::Server;
 ///
+/// #[derive(serde::Deserialize)]
+/// struct Params {
+///     id: u64,
+///     another_field: String,
+/// }
+///
 /// fn main() {
 ///     let mut server = Server::new(Default::default());
-///     let handler = HandlerFn::new(handler);
-///     server.get("/path/{id}/content/{another_field}/{final_field}", handler);
+///     server.get("/path/{id}/content/{another_field}/{final_field}", HandlerFn::new(handler));
+///     server.get("/users/{id}/files/{name}", HandlerFn::new(typed_handler));
+///     server.get("/params/{id}/content/{another_field}", HandlerFn::new(struct_handler));
 /// }
 ///
 /// async fn handler(Path(params): Path) -> String {
 ///     format!("path params: {:#?}", params)
 /// }
+///
+/// async fn typed_handler(Path((id, name)): Path<(u64, String)>) -> String {
+///     format!("user {id}, file {name}")
+/// }
+///
+/// async fn struct_handler(Path(params): Path<Params>) -> String {
+///     format!("id {}, field {}", params.id, params.another_field)
+/// }
 /// ```
-pub struct Path(pub HashMap<String, String>);
+pub struct Path<T = HashMap<String, String>>(pub T);
 
-impl FromRequest for Path {
-    type Rejection = ();
+impl<T: DeserializeOwned> FromRequest for Path<T> {
+    type Rejection = PathRejection;
 
     async fn from_request(request: &mut Request) -> Result<Self, Self::Rejection> {
-        Ok(Self(std::mem::take(&mut request.params)))
+        // Ordered parameters are missing if the request was not routed, i.e. passed to fallback.
+        let params: Vec<(&str, &str)> = match request.extensions().get::<PathParams>() {
+            Some(params) => params.iter().collect(),
+            None => request
+                .params
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str()))
+                .collect(),
+        };
+        T::deserialize(de::PathDeserializer::new(&params))
+            .map(Self)
+            .map_err(PathRejection)
+    }
+}
+
+/// Path parameters can't be deserialized into the requested type.
+#[derive(Debug)]
+pub struct PathRejection(pub PathDeserializationError);
+
+impl From<PathRejection> for Rejection {
+    fn from(rejection: PathRejection) -> Self {
+        Self::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_path_params",
+            format!("invalid path parameters: {}", rejection.0),
+        )
+    }
+}
+
+impl ResponsePart for PathRejection {
+    async fn apply(self, response: &mut Response) {
+        Rejection::from(self).apply(response).await;
     }
 }
@@ -0,0 +1,526 @@
+//! Deserializer of the path parameters.
+//!
+//! Parameters are deserialized as a map into structs and maps, as a sequence into tuples
+//! and sequences, single parameter is deserialized into primitives directly.
+//! Values are parsed from strings on demand, according to the requested type.
+use serde::{
+    de::{self, value::BorrowedStrDeserializer, DeserializeSeed, IntoDeserializer, Visitor},
+    forward_to_deserialize_any,
+};
+use std::fmt::Display;
+
+/// Failure of deserializing the path parameters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathDeserializationError(String);
+
+impl Display for PathDeserializationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for PathDeserializationError {}
+
+impl de::Error for PathDeserializationError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+type Params<'de> = &'de [(&'de str, &'de str)];
+
+pub(super) struct PathDeserializer<'de> {
+    params: Params<'de>,
+}
+
+impl<'de> PathDeserializer<'de> {
+    pub(super) fn new(params: Params<'de>) -> Self {
+        Self { params }
+    }
+
+    fn single_value(&self) -> Result<ValueDeserializer<'de>, PathDeserializationError> {
+        match self.params {
+            [(name, value)] => Ok(ValueDeserializer { name, value }),
+            params => Err(PathDeserializationError(format!(
+                "expected 1 path parameter, got {}",
+                params.len()
+            ))),
+        }
+    }
+}
+
+macro_rules! forward_to_single_value {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                self.single_value()?.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for PathDeserializer<'de> {
+    type Error = PathDeserializationError;
+
+    forward_to_single_value! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char deserialize_str
+        deserialize_string deserialize_bytes deserialize_byte_buf deserialize_option
+        deserialize_identifier
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(ParamsAccess::new(self.params))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        if self.params.len() != len {
+            return Err(PathDeserializationError(format!(
+                "expected {len} path parameters, got {}",
+                self.params.len()
+            )));
+        }
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(ParamsAccess::new(self.params))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.single_value()?
+            .deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+}
+
+/// Access to the parameters either as a sequence of values or as a map.
+struct ParamsAccess<'de> {
+    params: std::slice::Iter<'de, (&'de str, &'de str)>,
+    /// Value of the key returned last, when accessed as a map.
+    value: Option<ValueDeserializer<'de>>,
+}
+
+impl<'de> ParamsAccess<'de> {
+    fn new(params: Params<'de>) -> Self {
+        Self {
+            params: params.iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for ParamsAccess<'de> {
+    type Error = PathDeserializationError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        self.params
+            .next()
+            .map(|(name, value)| seed.deserialize(ValueDeserializer { name, value }))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.params.len())
+    }
+}
+
+impl<'de> de::MapAccess<'de> for ParamsAccess<'de> {
+    type Error = PathDeserializationError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let Some((name, value)) = self.params.next() else {
+            return Ok(None);
+        };
+        self.value = Some(ValueDeserializer { name, value });
+        seed.deserialize(BorrowedStrDeserializer::new(name))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| PathDeserializationError("value is accessed before key".into()))?;
+        seed.deserialize(value)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.params.len())
+    }
+}
+
+/// Deserializer of the single parameter value.
+struct ValueDeserializer<'de> {
+    name: &'de str,
+    value: &'de str,
+}
+
+impl ValueDeserializer<'_> {
+    fn parse<T>(&self, type_name: &str) -> Result<T, PathDeserializationError>
+    where
+        T: std::str::FromStr,
+        T::Err: Display,
+    {
+        self.value.parse().map_err(|err| {
+            PathDeserializationError(format!(
+                "failed to parse path parameter `{}` value {:?} as {type_name}: {err}",
+                self.name, self.value
+            ))
+        })
+    }
+
+    fn unsupported(&self, kind: &str) -> PathDeserializationError {
+        PathDeserializationError(format!(
+            "path parameter `{}` can't be deserialized as {kind}",
+            self.name
+        ))
+    }
+}
+
+macro_rules! parse_value {
+    ($($method:ident => $visit:ident($ty:ty))*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                visitor.$visit(self.parse::<$ty>(stringify!($ty))?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'de> {
+    type Error = PathDeserializationError;
+
+    parse_value! {
+        deserialize_bool => visit_bool(bool)
+        deserialize_i8 => visit_i8(i8)
+        deserialize_i16 => visit_i16(i16)
+        deserialize_i32 => visit_i32(i32)
+        deserialize_i64 => visit_i64(i64)
+        deserialize_i128 => visit_i128(i128)
+        deserialize_u8 => visit_u8(u8)
+        deserialize_u16 => visit_u16(u16)
+        deserialize_u32 => visit_u32(u32)
+        deserialize_u64 => visit_u64(u64)
+        deserialize_u128 => visit_u128(u128)
+        deserialize_f32 => visit_f32(f32)
+        deserialize_f64 => visit_f64(f64)
+        deserialize_char => visit_char(char)
+    }
+
+    forward_to_deserialize_any! {
+        str string identifier unit unit_struct ignored_any
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_borrowed_str(self.value)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_borrowed_bytes(self.value.as_bytes())
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_enum(self.value.into_deserializer())
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(self.unsupported("sequence"))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        Err(self.unsupported("tuple"))
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        Err(self.unsupported("tuple struct"))
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(self.unsupported("map"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        Err(self.unsupported("struct"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::collections::HashMap;
+
+    fn deserialize<'de, T: Deserialize<'de>>(
+        params: Params<'de>,
+    ) -> Result<T, PathDeserializationError> {
+        T::deserialize(PathDeserializer::new(params))
+    }
+
+    fn error<'de, T: Deserialize<'de> + std::fmt::Debug>(params: Params<'de>) -> String {
+        deserialize::<T>(params).unwrap_err().to_string()
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct UserId(u32);
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Kind {
+        User,
+        Team,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Member {
+        kind: Kind,
+        id: UserId,
+        name: String,
+        page: Option<u32>,
+    }
+
+    #[test]
+    fn single_primitive() {
+        assert_eq!(deserialize::<u32>(&[("id", "7")]), Ok(7));
+        assert_eq!(deserialize::<String>(&[("name", "bob")]), Ok("bob".into()));
+        assert_eq!(deserialize::<bool>(&[("flag", "true")]), Ok(true));
+
+        assert_eq!(error::<u32>(&[]), "expected 1 path parameter, got 0");
+        assert_eq!(
+            error::<u32>(&[("team", "1"), ("id", "7")]),
+            "expected 1 path parameter, got 2"
+        );
+        assert_eq!(
+            error::<u32>(&[("id", "abc")]),
+            "failed to parse path parameter `id` value \"abc\" as u32: invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn option() {
+        assert_eq!(deserialize::<Option<u32>>(&[("id", "7")]), Ok(Some(7)));
+        assert_eq!(
+            error::<Option<u32>>(&[]),
+            "expected 1 path parameter, got 0"
+        );
+    }
+
+    #[test]
+    fn newtype() {
+        assert_eq!(deserialize::<UserId>(&[("id", "7")]), Ok(UserId(7)));
+        assert_eq!(
+            error::<UserId>(&[("id", "-7")]),
+            "failed to parse path parameter `id` value \"-7\" as u32: invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn enumeration() {
+        assert_eq!(deserialize::<Kind>(&[("kind", "team")]), Ok(Kind::Team));
+        assert_eq!(
+            error::<Kind>(&[("kind", "bot")]),
+            "unknown variant `bot`, expected `user` or `team`"
+        );
+        assert_eq!(
+            error::<Kind>(&[("kind", "user"), ("id", "7")]),
+            "expected 1 path parameter, got 2"
+        );
+    }
+
+    #[test]
+    fn tuple() {
+        assert_eq!(
+            deserialize::<(String, u32)>(&[("team", "core"), ("id", "7")]),
+            Ok(("core".into(), 7))
+        );
+        assert_eq!(
+            error::<(String, u32)>(&[("team", "core")]),
+            "expected 2 path parameters, got 1"
+        );
+        assert_eq!(
+            error::<(String,)>(&[("team", "core"), ("id", "7")]),
+            "expected 1 path parameters, got 2"
+        );
+        assert_eq!(
+            error::<(String, u32)>(&[("team", "core"), ("id", "x")]),
+            "failed to parse path parameter `id` value \"x\" as u32: invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn sequence() {
+        assert_eq!(
+            deserialize::<Vec<u32>>(&[("team", "1"), ("id", "7")]),
+            Ok(vec![1, 7])
+        );
+    }
+
+    #[test]
+    fn structure() {
+        assert_eq!(
+            deserialize::<Member>(&[("kind", "user"), ("id", "7"), ("name", "bob")]),
+            Ok(Member {
+                kind: Kind::User,
+                id: UserId(7),
+                name: "bob".into(),
+                page: None,
+            })
+        );
+        assert_eq!(
+            deserialize::<Member>(&[
+                ("kind", "team"),
+                ("id", "7"),
+                ("name", "core"),
+                ("page", "2"),
+            ]),
+            Ok(Member {
+                kind: Kind::Team,
+                id: UserId(7),
+                name: "core".into(),
+                page: Some(2),
+            })
+        );
+        assert_eq!(
+            error::<Member>(&[("kind", "user"), ("id", "7")]),
+            "missing field `name`"
+        );
+    }
+
+    #[test]
+    fn map() {
+        let expected = HashMap::from([("team".to_string(), 1), ("id".to_string(), 7)]);
+        assert_eq!(
+            deserialize::<HashMap<String, u32>>(&[("team", "1"), ("id", "7")]),
+            Ok(expected)
+        );
+        assert_eq!(
+            deserialize::<HashMap<&str, &str>>(&[("name", "bob")]),
+            Ok(HashMap::from([("name", "bob")]))
+        );
+        assert_eq!(
+            error::<HashMap<String, u32>>(&[("id", "x")]),
+            "failed to parse path parameter `id` value \"x\" as u32: invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn nested_collections_are_unsupported() {
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Nested {
+            ids: Vec<u32>,
+        }
+
+        assert_eq!(
+            error::<Nested>(&[("ids", "1")]),
+            "path parameter `ids` can't be deserialized as sequence"
+        );
+        assert_eq!(
+            error::<Vec<HashMap<String, String>>>(&[("id", "1")]),
+            "path parameter `id` can't be deserialized as map"
+        );
+        assert_eq!(
+            error::<Vec<(u32, u32)>>(&[("id", "1")]),
+            "path parameter `id` can't be deserialized as tuple"
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use super::{rejection::Rejection, FromRequest, Request};
+use crate::{frontend::response::ResponsePart, server::Response};
+use http::StatusCode;
+use serde::de::DeserializeOwned;
+
+/// Extracts percent-decoded query parameters of the request URI.
+///
+/// Repeated keys are collected into sequences, missing keys are allowed for `Option` fields.
+/// Rejects with 400 if the query can't be deserialized into `T`.
+///
+/// Example:
+///
+/// ```rust
+/// use weaver::frontend::request::query::Query;
+/// use weaver::frontend::handler::HandlerFn;
+/// use weaver::server::Server;
+///
+/// #[derive(serde::Deserialize)]
+/// struct Pagination {
+///     page: Option<u32>,
+///     #[serde(default)]
+///     tag: Vec<String>,
+/// }
+///
+/// fn main() {
+///     let mut server = Server::new(Default::default());
+///     server.get("/items", HandlerFn::new(handler));
+/// }
+///
+/// // i.e. `/items?page=2&tag=new&tag=sale`
+/// async fn handler(Query(pagination): Query<Pagination>) -> String {
+///     format!("page {}, tags {:?}", pagination.page.unwrap_or(1), pagination.tag)
+/// }
+/// ```
+pub struct Query<T>(pub T);
+
+impl<T: DeserializeOwned> FromRequest for Query<T> {
+    type Rejection = QueryRejection;
+
+    async fn from_request(request: &mut Request) -> Result<Self, Self::Rejection> {
+        let query = request.uri().query().unwrap_or_default();
+        serde_html_form::from_str(query)
+            .map(Self)
+            .map_err(QueryRejection)
+    }
+}
+
+/// Query can't be deserialized into the requested type.
+#[derive(Debug)]
+pub struct QueryRejection(pub serde::de::value::Error);
+
+impl From<QueryRejection> for Rejection {
+    fn from(rejection: QueryRejection) -> Self {
+        Self::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_query",
+            format!("invalid query: {}", rejection.0),
+        )
+    }
+}
+
+impl ResponsePart for QueryRejection {
+    async fn apply(self, response: &mut Response) {
+        Rejection::from(self).apply(response).await;
+    }
+}
+
+/// Extracts the query of the request URI as is, without decoding.
+/// `None` if the URI has no query.
+pub struct RawQuery(pub Option<String>);
+
+impl FromRequest for RawQuery {
+    type Rejection = ();
+
+    async fn from_request(request: &mut Request) -> Result<Self, Self::Rejection> {
+        Ok(Self(request.uri().query().map(ToString::to_string)))
+    }
+}
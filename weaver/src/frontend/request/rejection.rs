@@ -0,0 +1,187 @@
+//! Common representation of the extractors rejections.
+//!
+//! Every built-in rejection is converted into [Rejection] when applied to the response,
+//! so clients get the same machine-readable body regardless of the extractor:
+//!
+//! ```json
+//! {"error": "invalid_query", "message": "invalid query: missing field `page`"}
+//! ```
+//!
+//! Some rejections carry additional `details`, i.e. position of the JSON error:
+//!
+//! ```json
+//! {"error": "invalid_json", "message": "...", "details": {"line": 1, "column": 27}}
+//! ```
+//!
+//! Rendering could be replaced for the whole server with [Server::rejection_handler].
+use super::super::response::ResponsePart;
+use crate::server::{
+    strip_body, BodyError, Request, RequestHandler, Response, SharedRequestHandler,
+};
+use http::{header, HeaderValue, Method, StatusCode};
+use std::{fmt::Write as _, rc::Rc};
+
+#[cfg(doc)]
+use crate::server::Server;
+
+/// Request rejected by an extractor.
+///
+/// Could be used as a rejection of the custom extractors as well, to be rendered
+/// in the same way as the built-in ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rejection {
+    pub status: StatusCode,
+    /// Machine-readable kind of the rejection, i.e. `invalid_json`.
+    pub kind: &'static str,
+    /// Human-readable description of the rejection.
+    pub message: String,
+    /// Machine-readable details of the rejection, if any.
+    pub details: Option<RejectionDetails>,
+}
+
+impl Rejection {
+    pub fn new(status: StatusCode, kind: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            kind,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: RejectionDetails) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    /// JSON body of the rejection, see [ResponsePart] implementation.
+    fn to_json(&self) -> String {
+        let mut body = String::from("{\"error\":");
+        push_json_string(&mut body, self.kind);
+        body.push_str(",\"message\":");
+        push_json_string(&mut body, &self.message);
+        if let Some(details) = &self.details {
+            body.push_str(",\"details\":");
+            details.push_json(&mut body);
+        }
+        body.push('}');
+        body
+    }
+}
+
+/// Position of the malformed input within the request, fields are omitted if unknown.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RejectionDetails {
+    /// Path to the invalid value, i.e. `tags[1]`.
+    pub path: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl RejectionDetails {
+    fn push_json(&self, out: &mut String) {
+        let mut fields = Vec::new();
+        if let Some(path) = &self.path {
+            let mut field = String::from("\"path\":");
+            push_json_string(&mut field, path);
+            fields.push(field);
+        }
+        if let Some(line) = self.line {
+            fields.push(format!("\"line\":{line}"));
+        }
+        if let Some(column) = self.column {
+            fields.push(format!("\"column\":{column}"));
+        }
+        out.push('{');
+        out.push_str(&fields.join(","));
+        out.push('}');
+    }
+}
+
+/// Append the value as JSON string literal.
+fn push_json_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Failure of collecting the request body: 413 if the body limit is exceeded, 400 otherwise.
+impl From<BodyError> for Rejection {
+    fn from(err: BodyError) -> Self {
+        match err {
+            BodyError::LengthLimit { .. } => Self::new(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "payload_too_large",
+                err.to_string(),
+            ),
+            BodyError::Transport(_) => {
+                Self::new(StatusCode::BAD_REQUEST, "body_read_failed", err.to_string())
+            }
+        }
+    }
+}
+
+/// Renders `{"error": kind, "message": message, "details": details}` JSON body
+/// with the rejection status, `details` are omitted if there are none.
+///
+/// Rejection itself is kept in the response extensions, so it could be rendered differently
+/// later on, see [Server::rejection_handler].
+impl ResponsePart for Rejection {
+    async fn apply(self, response: &mut Response) {
+        let body = self.to_json();
+        (
+            self.status,
+            (
+                header::CONTENT_TYPE,
+                HeaderValue::from_static(mime::APPLICATION_JSON.as_ref()),
+            ),
+            body,
+        )
+            .apply(response)
+            .await;
+        response.extensions_mut().insert(self);
+    }
+}
+
+pub(crate) type RenderRejection = Rc<dyn Fn(Rejection) -> Response>;
+
+/// Re-renders responses of the rejected requests with the application-provided function.
+pub(crate) struct RejectionRenderer {
+    pub(crate) inner: SharedRequestHandler,
+    pub(crate) render: RenderRejection,
+}
+
+#[async_trait::async_trait(?Send)]
+impl RequestHandler for RejectionRenderer {
+    async fn handle_async(&self, request: Request) -> Response {
+        let is_head = request.method() == Method::HEAD;
+        let mut response = self.inner.as_handler().handle_async(request).await;
+        let Some(rejection) = response.extensions_mut().remove::<Rejection>() else {
+            return response;
+        };
+        let mut rendered = (self.render)(rejection);
+        // Keep headers set by the middlewares, i.e. request id, but not the ones describing
+        // the original body.
+        let mut headers = std::mem::take(response.headers_mut());
+        headers.remove(header::CONTENT_TYPE);
+        headers.remove(header::CONTENT_LENGTH);
+        headers.extend(std::mem::take(rendered.headers_mut()));
+        *rendered.headers_mut() = headers;
+        if is_head {
+            strip_body(&mut rendered);
+        }
+        rendered
+    }
+}
@@ -0,0 +1,72 @@
+use super::{rejection::Rejection, FromRequest, Request};
+use crate::server::{RequestHandler, Response, SharedRequestHandler, StateMap, StateType};
+use http::StatusCode;
+
+/// Extracts the state shared with [crate::server::Server::with_state]
+/// or [crate::frontend::routing::Group::with_state].
+///
+/// State is cloned from the value registered for its type, the innermost group one takes
+/// precedence. Handlers requiring unregistered state fail the server start.
+///
+/// Example:
+///
+/// ```rust
+/// use std::{cell::Cell, rc::Rc};
+/// use weaver::frontend::request::state::State;
+/// use weaver::frontend::handler::HandlerFn;
+/// use weaver::server::Server;
+///
+/// #[derive(Clone)]
+/// struct Counter(Rc<Cell<u64>>);
+///
+/// fn main() {
+///     let mut server = Server::new(Default::default());
+///     server.with_state(Counter(Rc::default()));
+///     server.get("/hits", HandlerFn::new(handler));
+/// }
+///
+/// async fn handler(State(counter): State<Counter>) -> String {
+///     counter.0.set(counter.0.get() + 1);
+///     format!("hits: {}", counter.0.get())
+/// }
+/// ```
+pub struct State<T>(pub T);
+
+impl<T: Clone + 'static> FromRequest for State<T> {
+    type Rejection = Rejection;
+
+    async fn from_request(request: &mut Request) -> Result<Self, Self::Rejection> {
+        // Could be missing only if the handler is called outside of the server it's checked by.
+        request.states.get::<T>().cloned().map(Self).ok_or_else(|| {
+            Rejection::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "missing_state",
+                format!("state `{}` is not registered", std::any::type_name::<T>()),
+            )
+        })
+    }
+
+    fn required_state() -> Option<StateType> {
+        Some(StateType::of::<T>())
+    }
+}
+
+/// Provides the group states to the wrapped handler.
+pub(crate) struct StateProvider {
+    pub(crate) states: StateMap,
+    pub(crate) inner: SharedRequestHandler,
+}
+
+#[async_trait::async_trait(?Send)]
+impl RequestHandler for StateProvider {
+    async fn handle_async(&self, mut request: Request) -> Response {
+        request.states.push(self.states.clone());
+        self.inner.as_handler().handle_async(request).await
+    }
+
+    fn required_states(&self) -> Vec<StateType> {
+        let mut states = self.inner.as_handler().required_states();
+        states.retain(|state| !self.states.contains(*state));
+        states
+    }
+}
@@ -0,0 +1,144 @@
+use super::{rejection::Rejection, FromRequest, Request};
+use crate::{
+    frontend::response::ResponsePart,
+    server::{tls::PeerCertificates, Response},
+};
+use http::StatusCode;
+use std::net::IpAddr;
+use x509_parser::{certificate::X509Certificate, extensions::GeneralName, prelude::FromDer};
+
+/// Extracts certificate the client authenticated with over mutual TLS.
+///
+/// Rejects with 401 if connection is not authenticated with client certificate.
+///
+/// Example:
+///
+/// ```rust
+/// use weaver::frontend::request::tls::ClientCertificate;
+/// use weaver::frontend::handler::HandlerFn;
+/// use weaver::server::Server;
+///
+/// fn main() {
+///     let mut server = Server::new(Default::default());
+///     server.get("/whoami", HandlerFn::new(handler));
+/// }
+///
+/// async fn handler(certificate: ClientCertificate) -> String {
+///     format!("hello, {}", certificate.common_name.unwrap_or_default())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClientCertificate {
+    /// Raw certificate chain presented by the client.
+    pub chain: PeerCertificates,
+    /// Subject of the leaf certificate, i.e. `O=cluster, CN=service`.
+    pub subject: String,
+    /// Common name of the leaf certificate subject, if any.
+    pub common_name: Option<String>,
+    /// Subject alternative names of the leaf certificate.
+    pub subject_alt_names: Vec<SubjectAltName>,
+}
+
+/// Subject alternative name of the certificate.
+/// Only commonly used kinds of names are represented.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubjectAltName {
+    Dns(String),
+    Email(String),
+    Uri(String),
+    Ip(IpAddr),
+}
+
+impl ClientCertificate {
+    fn parse(chain: PeerCertificates) -> Result<Self, ClientCertificateRejection> {
+        let (_, leaf) = X509Certificate::from_der(chain.leaf())
+            .map_err(|err| ClientCertificateRejection::Malformed(err.to_string()))?;
+
+        let common_name = leaf
+            .subject()
+            .iter_common_name()
+            .next()
+            .and_then(|cn| cn.as_str().ok())
+            .map(ToString::to_string);
+        let subject_alt_names = leaf
+            .subject_alternative_name()
+            .map_err(|err| ClientCertificateRejection::Malformed(err.to_string()))?
+            .map(|extension| {
+                extension
+                    .value
+                    .general_names
+                    .iter()
+                    .filter_map(SubjectAltName::from_general_name)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Self {
+            subject: leaf.subject().to_string(),
+            common_name,
+            subject_alt_names,
+            chain,
+        })
+    }
+}
+
+impl SubjectAltName {
+    fn from_general_name(name: &GeneralName) -> Option<Self> {
+        match name {
+            GeneralName::DNSName(name) => Some(Self::Dns(name.to_string())),
+            GeneralName::RFC822Name(email) => Some(Self::Email(email.to_string())),
+            GeneralName::URI(uri) => Some(Self::Uri(uri.to_string())),
+            GeneralName::IPAddress(octets) => match octets.len() {
+                4 => <[u8; 4]>::try_from(*octets).ok().map(IpAddr::from),
+                16 => <[u8; 16]>::try_from(*octets).ok().map(IpAddr::from),
+                _ => None,
+            }
+            .map(Self::Ip),
+            _ => None,
+        }
+    }
+}
+
+impl FromRequest for ClientCertificate {
+    type Rejection = ClientCertificateRejection;
+
+    async fn from_request(request: &mut Request) -> Result<Self, Self::Rejection> {
+        let chain = request
+            .extensions()
+            .get::<PeerCertificates>()
+            .cloned()
+            .ok_or(ClientCertificateRejection::Missing)?;
+        Self::parse(chain)
+    }
+}
+
+#[derive(Debug)]
+pub enum ClientCertificateRejection {
+    /// Connection is not authenticated with client certificate.
+    Missing,
+    /// Client certificate passed verification, but can't be parsed.
+    Malformed(String),
+}
+
+impl From<ClientCertificateRejection> for Rejection {
+    fn from(rejection: ClientCertificateRejection) -> Self {
+        match rejection {
+            ClientCertificateRejection::Missing => Self::new(
+                StatusCode::UNAUTHORIZED,
+                "client_certificate_required",
+                "client certificate is required",
+            ),
+            ClientCertificateRejection::Malformed(err) => Self::new(
+                StatusCode::BAD_REQUEST,
+                "malformed_client_certificate",
+                format!("malformed client certificate: {err}"),
+            ),
+        }
+    }
+}
+
+impl ResponsePart for ClientCertificateRejection {
+    async fn apply(self, response: &mut Response) {
+        Rejection::from(self).apply(response).await;
+    }
+}
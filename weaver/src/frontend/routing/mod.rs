@@ -1,13 +1,23 @@
 use crate::{
-    frontend::middleware::{Next, SharedMiddleware},
-    server::{RequestHandler, Route, Server, SharedRequestHandler},
+    frontend::{
+        middleware::{Next, SharedMiddleware},
+        request::{
+            rejection::{Rejection, RejectionRenderer, RenderRejection},
+            state::StateProvider,
+        },
+    },
+    server::{BodyLimit, RequestHandler, Response, Route, Server, SharedRequestHandler, StateMap},
 };
+use std::rc::Rc;
 
 #[derive(Clone)]
 pub struct Group {
     base_path: String,
     middlewares: Vec<SharedMiddleware>,
+    body_limit: Option<BodyLimit>,
+    states: StateMap,
     routes: Vec<InnerRoute>,
+    fallbacks: Vec<InnerFallback>,
 }
 
 impl Group {
@@ -15,10 +25,40 @@ impl Group {
         Self {
             base_path: String::new(),
             middlewares: Default::default(),
+            body_limit: None,
+            states: StateMap::default(),
             routes: Vec::new(),
+            fallbacks: Vec::new(),
         }
     }
 
+    /// Register handler for requests under the group path which didn't match any route.
+    ///
+    /// Group middlewares are applied to the fallback as well.
+    pub fn fallback(&mut self, handler: impl Into<SharedRequestHandler>) -> &mut Self {
+        self.fallbacks.push(InnerFallback {
+            prefix: String::new(),
+            handler: Next::from(handler.into()),
+        });
+        self
+    }
+
+    /// Override the server body limit for the group routes.
+    /// Limits set on the routes themselves take precedence.
+    pub fn body_limit(&mut self, limit: BodyLimit) -> &mut Self {
+        self.body_limit = Some(limit);
+        self
+    }
+
+    /// Share the state with the group routes, fallbacks and middlewares.
+    ///
+    /// Takes precedence over the state of the same type registered on the server
+    /// or the outer groups, see [Server::with_state].
+    pub fn with_state<T: 'static>(&mut self, state: T) -> &mut Self {
+        self.states.insert(state);
+        self
+    }
+
     pub fn path(&mut self, path: impl Into<String>) -> &mut Self {
         self.base_path = path.into();
         self
@@ -51,18 +91,38 @@ impl Group {
             .into_iter()
             .map(|mut route| {
                 route.route.path = concat_path(&group.base_path, &route.route.path);
-                route.handler = group.apply_middlewares(route.handler);
+                route.route.body_limit = route.route.body_limit.or(group.body_limit);
+                route.handler = group.wrap_handler(route.handler);
                 route
             });
         self.routes.extend(wrapped_routes);
+        let wrapped_fallbacks =
+            std::mem::take(&mut group.fallbacks)
+                .into_iter()
+                .map(|mut fallback| {
+                    fallback.prefix = concat_path(&group.base_path, &fallback.prefix);
+                    fallback.handler = group.wrap_handler(fallback.handler);
+                    fallback
+                });
+        self.fallbacks.extend(wrapped_fallbacks);
         Ok(self)
     }
 
-    fn apply_middlewares(&self, handler: Next) -> Next {
-        self.middlewares
+    /// Wrap the handler with the group middlewares and states.
+    fn wrap_handler(&self, handler: Next) -> Next {
+        let handler = self
+            .middlewares
             .iter()
             .rev()
-            .fold(handler, |stack, middleware| middleware.clone().wrap(stack))
+            .fold(handler, |stack, middleware| middleware.clone().wrap(stack));
+        if self.states.is_empty() {
+            return handler;
+        }
+        // States are provided outside of the middlewares, so they could extract them too.
+        Next::from(SharedRequestHandler::new(StateProvider {
+            states: self.states.clone(),
+            inner: handler.into(),
+        }))
     }
 
     pub fn take(&mut self) -> Self {
@@ -158,6 +218,38 @@ impl Default for Group {
 }
 
 impl Server {
+    /// Register middleware wrapping every request of the server.
+    ///
+    /// Unlike [Group::middleware], it also applies to the requests which didn't match any route,
+    /// including fallbacks and error responses. Path params are not resolved yet when it's called.
+    /// Middlewares are applied in the order of registration, first one is the outermost.
+    pub fn middleware(&mut self, middleware: impl Into<SharedMiddleware>) -> &mut Self {
+        let middleware = middleware.into();
+        self.require_states(middleware.as_middleware().required_states(), || {
+            "server middleware".into()
+        });
+        self.layer(move |handler| middleware.clone().wrap(Next::from(handler)).into())
+    }
+
+    /// Customize rendering of the rejected requests, i.e. to match the API error format.
+    ///
+    /// Called with every [Rejection] the handlers and middlewares respond with, including
+    /// the ones of the built-in extractors. Headers set by the middlewares are preserved.
+    /// Replaces previously set handler, if any.
+    pub fn rejection_handler(
+        &mut self,
+        render: impl Fn(Rejection) -> Response + 'static,
+    ) -> &mut Self {
+        let render: RenderRejection = Rc::new(render);
+        self.outer_layer(move |inner| {
+            RejectionRenderer {
+                inner,
+                render: render.clone(),
+            }
+            .into()
+        })
+    }
+
     pub fn group(
         &mut self,
         mut group: impl AsMut<Group>,
@@ -165,9 +257,15 @@ impl Server {
         let mut group = group.as_mut().take();
         for mut route in std::mem::take(&mut group.routes).into_iter() {
             route.route.path = concat_path(&group.base_path, &route.route.path);
-            let handler: SharedRequestHandler = group.apply_middlewares(route.handler).into();
+            route.route.body_limit = route.route.body_limit.or(group.body_limit);
+            let handler: SharedRequestHandler = group.wrap_handler(route.handler).into();
             self.route(route.route, handler)?;
         }
+        for fallback in std::mem::take(&mut group.fallbacks).into_iter() {
+            let prefix = concat_path(&group.base_path, &fallback.prefix);
+            let handler: SharedRequestHandler = group.wrap_handler(fallback.handler).into();
+            self.fallback_at(&prefix, handler);
+        }
         Ok(self)
     }
 }
@@ -182,6 +280,13 @@ fn concat_path(a: impl Into<String>, b: impl Into<String>) -> String {
     )
 }
 
+#[derive(Clone)]
+struct InnerFallback {
+    /// Path prefix relative to the group.
+    prefix: String,
+    handler: Next,
+}
+
 #[derive(Clone)]
 struct InnerRoute {
     route: Route,
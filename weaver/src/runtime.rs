@@ -2,16 +2,18 @@
 //! Various runtimes for hyper
 use std::{
     fmt::Display,
+    future::{pending, Future},
     mem::MaybeUninit,
-    pin::Pin,
+    pin::{pin, Pin},
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
 
 use futures_io::{AsyncRead, AsyncWrite};
 use pin_project_lite::pin_project;
 use tarantool::{
-    fiber::{self},
-    network::client::tcp::TcpStream,
+    fiber::{self, r#async::timeout},
+    time::Instant as FiberInstant,
 };
 
 #[derive(Clone)]
@@ -41,20 +43,76 @@ where
     }
 }
 
+/// Timer for hyper, driven by the fiber clock.
+///
+/// Sleeps register their deadlines in the tarantool async runtime,
+/// so they must be polled within [fiber::block_on].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TarantoolHyperTimer;
+
+impl hyper::rt::Timer for TarantoolHyperTimer {
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn hyper::rt::Sleep>> {
+        Box::pin(TarantoolSleep::new(duration))
+    }
+
+    fn sleep_until(&self, deadline: Instant) -> Pin<Box<dyn hyper::rt::Sleep>> {
+        Box::pin(TarantoolSleep::new(
+            deadline.saturating_duration_since(Instant::now()),
+        ))
+    }
+}
+
+/// Future which completes once the fiber clock reaches the deadline.
+#[derive(Debug)]
+pub struct TarantoolSleep {
+    deadline: FiberInstant,
+}
+
+impl TarantoolSleep {
+    pub fn new(duration: Duration) -> Self {
+        Self::until(fiber::clock().saturating_add(duration))
+    }
+
+    pub fn until(deadline: FiberInstant) -> Self {
+        Self { deadline }
+    }
+
+    pub fn deadline(&self) -> FiberInstant {
+        self.deadline
+    }
+}
+
+impl Future for TarantoolSleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Tarantool runtime is woken up on deadlines registered through its timeout
+        // future only, so a never completing one is used to register ours.
+        let expired = pin!(timeout::deadline(
+            self.deadline,
+            pending::<Result<(), ()>>()
+        ));
+        expired.poll(cx).map(|_| ())
+    }
+}
+
+impl hyper::rt::Sleep for TarantoolSleep {}
+
 pin_project! {
-    pub struct TarantoolAsyncIO {
+    /// Adapter of the tarantool-driven stream to the hyper IO traits.
+    pub struct TarantoolAsyncIO<S> {
         #[pin]
-        stream: TcpStream,
+        stream: S,
     }
 }
 
-impl TarantoolAsyncIO {
-    pub fn new(stream: TcpStream) -> Self {
+impl<S> TarantoolAsyncIO<S> {
+    pub fn new(stream: S) -> Self {
         Self { stream }
     }
 }
 
-impl hyper::rt::Read for TarantoolAsyncIO {
+impl<S: AsyncRead> hyper::rt::Read for TarantoolAsyncIO<S> {
     fn poll_read(
         self: Pin<&mut Self>,
         cx: &mut Context<'_>,
@@ -76,7 +134,7 @@ impl hyper::rt::Read for TarantoolAsyncIO {
     }
 }
 
-impl hyper::rt::Write for TarantoolAsyncIO {
+impl<S: AsyncWrite> hyper::rt::Write for TarantoolAsyncIO<S> {
     fn poll_write(
         self: Pin<&mut Self>,
         cx: &mut Context<'_>,
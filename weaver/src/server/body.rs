@@ -0,0 +1,125 @@
+//! Request body with the size limit enforced.
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use hyper::body::{Body as HttpBody, Bytes, Frame, Incoming, SizeHint};
+use pin_project_lite::pin_project;
+
+/// Maximum size of the request body.
+///
+/// Bodies are checked against the limit by `Content-Length` before the handler is called,
+/// and while they are streamed, so no more than the limit is ever buffered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyLimit {
+    Bytes(usize),
+    Unlimited,
+}
+
+impl BodyLimit {
+    /// Default limit of the server, 2 MiB.
+    pub const DEFAULT: Self = Self::Bytes(2 * 1024 * 1024);
+
+    fn bytes(self) -> Option<u64> {
+        match self {
+            Self::Bytes(limit) => Some(limit as u64),
+            Self::Unlimited => None,
+        }
+    }
+
+    /// Whether the body of declared length exceeds the limit.
+    pub(crate) fn is_exceeded_by(self, length: u64) -> bool {
+        self.bytes().is_some_and(|limit| length > limit)
+    }
+}
+
+impl Default for BodyLimit {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum BodyError {
+    #[error("request body exceeds the limit of {limit} bytes")]
+    LengthLimit { limit: usize },
+    #[error("failed to read request body: {0}")]
+    Transport(#[from] hyper::Error),
+}
+
+pin_project! {
+    /// Body of the incoming request.
+    ///
+    /// Reading the body fails with [BodyError::LengthLimit] as soon as the route [BodyLimit]
+    /// is exceeded.
+    ///
+    /// Default body is empty, it's left in place of the body taken by the extractor.
+    #[derive(Default)]
+    pub struct RequestBody {
+        #[pin]
+        inner: Option<Incoming>,
+        limit: BodyLimit,
+        read: u64,
+    }
+}
+
+impl RequestBody {
+    pub(crate) fn new(inner: Incoming, limit: BodyLimit) -> Self {
+        Self {
+            inner: Some(inner),
+            limit,
+            read: 0,
+        }
+    }
+
+    /// Replace the limit once the route is resolved, the data read so far is counted against it.
+    pub(crate) fn set_limit(&mut self, limit: BodyLimit) {
+        self.limit = limit;
+    }
+
+    /// Limit the body is checked against.
+    pub fn limit(&self) -> BodyLimit {
+        self.limit
+    }
+}
+
+impl HttpBody for RequestBody {
+    type Data = Bytes;
+    type Error = BodyError;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = self.project();
+        let Some(inner) = this.inner.as_pin_mut() else {
+            return Poll::Ready(None);
+        };
+        let frame = match inner.poll_frame(cx) {
+            Poll::Ready(Some(Ok(frame))) => frame,
+            Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(err.into()))),
+            Poll::Ready(None) => return Poll::Ready(None),
+            Poll::Pending => return Poll::Pending,
+        };
+        if let Some(data) = frame.data_ref() {
+            *this.read += data.len() as u64;
+            if let BodyLimit::Bytes(limit) = *this.limit {
+                if this.limit.is_exceeded_by(*this.read) {
+                    return Poll::Ready(Some(Err(BodyError::LengthLimit { limit })));
+                }
+            }
+        }
+        Poll::Ready(Some(Ok(frame)))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.as_ref().map_or(true, HttpBody::is_end_stream)
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner
+            .as_ref()
+            .map_or_else(|| SizeHint::with_exact(0), HttpBody::size_hint)
+    }
+}
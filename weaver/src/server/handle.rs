@@ -0,0 +1,327 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+    time::Duration,
+};
+
+use log::{debug, info, warn};
+use tarantool::fiber::{self, FiberId};
+
+use super::{Error, LocalAddr};
+
+/// Time given to the aborted connections to exit, see [ServerHandle::shutdown].
+const ABORT_GRACE: Duration = Duration::from_millis(100);
+
+/// Handle to the running server.
+///
+/// Obtained from [super::Server::defer] or [super::Server::into_fiber].
+/// Cloning the handle is cheap, all clones control the same server instance.
+///
+/// Dropping the handle does not stop the server.
+#[derive(Clone)]
+pub struct ServerHandle {
+    lifecycle: Rc<Lifecycle>,
+}
+
+impl ServerHandle {
+    pub(crate) fn new(lifecycle: Rc<Lifecycle>) -> Self {
+        Self { lifecycle }
+    }
+
+    /// Gracefully shutdown the server.
+    ///
+    /// Server stops accepting new connections right away and asks every in-flight connection
+    /// to finish: requests being processed are completed, idle keep-alive connections are closed.
+    /// Connections which are still alive when `timeout` is elapsed are aborted.
+    ///
+    /// Yields current fiber until the server is fully stopped or the deadline is exceeded,
+    /// returns [Error::ShutdownTimeout] if some connections had to be aborted.
+    ///
+    /// Aborted connections waiting on their sockets are closed right away. Connections whose
+    /// handlers are still running are not interrupted: they are closed once the handler
+    /// returns, in the background, so the call never outlives the deadline by much.
+    pub fn shutdown(&self, timeout: Duration) -> Result<(), Error> {
+        self.initiate_shutdown();
+        if self.wait(timeout) {
+            info!(ctx = self.lifecycle.log_ctx(); "server is gracefully stopped");
+            return Ok(());
+        }
+
+        let aborted = self.lifecycle.abort();
+        warn!(
+            ctx = self.lifecycle.log_ctx();
+            "server shutdown deadline exceeded, aborting {aborted} connections"
+        );
+        // Give the woken up connections a chance to exit, running handlers are not waited for.
+        self.wait(ABORT_GRACE);
+        Err(Error::ShutdownTimeout(aborted))
+    }
+
+    /// Stop accepting new connections and ask in-flight connections to finish,
+    /// without waiting for them.
+    ///
+    /// Use [Self::wait] or [Self::is_finished] to track the progress.
+    pub fn initiate_shutdown(&self) {
+        self.lifecycle.initiate_shutdown();
+    }
+
+    /// Yield current fiber until the server is fully stopped or `timeout` is elapsed.
+    ///
+    /// Returns `true` if the server is stopped.
+    pub fn wait(&self, timeout: Duration) -> bool {
+        let deadline = fiber::clock().saturating_add(timeout);
+        while !self.is_finished() {
+            if !self.lifecycle.finished.wait_deadline(deadline) && !self.is_finished() {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Whether the server is stopped: it doesn't accept connections and
+    /// all of the accepted connections are finished.
+    pub fn is_finished(&self) -> bool {
+        self.lifecycle.is_finished()
+    }
+
+    /// Whether the shutdown was requested.
+    pub fn is_shutting_down(&self) -> bool {
+        self.lifecycle.is_shutting_down()
+    }
+
+    /// Address of the main listener, see [super::ServerConfig::bind].
+    ///
+    /// If port `0` was requested, the port picked by the system is reported.
+    pub fn local_addr(&self) -> &LocalAddr {
+        &self.lifecycle.local_addrs[0]
+    }
+
+    /// Addresses of all listeners: the main one goes first, followed by
+    /// [super::ServerConfig::listeners] in the configured order.
+    pub fn local_addrs(&self) -> &[LocalAddr] {
+        &self.lifecycle.local_addrs
+    }
+
+    /// Amount of connections currently being served, including the accepted ones
+    /// whose fibers are not started yet.
+    pub fn active_connections(&self) -> usize {
+        self.lifecycle.active_connections()
+    }
+
+    /// Amount of connections which failed to be accepted or spawned since the server start,
+    /// across all of the listeners.
+    pub fn accept_failures(&self) -> u64 {
+        self.lifecycle.accept_failures.get()
+    }
+}
+
+/// Shared state between the server fibers and its [ServerHandle].
+///
+/// Fibers serving the server are registered here, so they could be woken up on shutdown:
+/// they might be blocked on a socket, where regular waker notifications can't reach them.
+/// Connections are registered before their fibers are started, so they are accounted
+/// by the connection limit and the shutdown right after being accepted.
+pub(crate) struct Lifecycle {
+    server_name: String,
+    local_addrs: Vec<LocalAddr>,
+    shutting_down: Cell<bool>,
+    aborted: Cell<bool>,
+    accept_fibers: RefCell<Vec<FiberId>>,
+    /// Amount of listeners which are not finished yet, including the ones
+    /// whose fibers are not started.
+    accepting: Cell<usize>,
+    next_connection_id: Cell<u64>,
+    connections: RefCell<HashMap<u64, ConnectionEntry>>,
+    accept_failures: Cell<u64>,
+    connection_finished: fiber::Cond,
+    finished: fiber::Cond,
+}
+
+impl Lifecycle {
+    pub(crate) fn new(server_name: impl Into<String>, local_addrs: Vec<LocalAddr>) -> Self {
+        Self {
+            server_name: server_name.into(),
+            local_addrs,
+            shutting_down: Cell::new(false),
+            aborted: Cell::new(false),
+            accept_fibers: Default::default(),
+            accepting: Cell::new(0),
+            next_connection_id: Cell::new(0),
+            connections: Default::default(),
+            accept_failures: Cell::new(0),
+            connection_finished: fiber::Cond::new(),
+            finished: fiber::Cond::new(),
+        }
+    }
+
+    pub(crate) fn is_shutting_down(&self) -> bool {
+        self.shutting_down.get()
+    }
+
+    pub(crate) fn is_aborted(&self) -> bool {
+        self.aborted.get()
+    }
+
+    fn is_finished(&self) -> bool {
+        self.accepting.get() == 0 && self.connections.borrow().is_empty()
+    }
+
+    fn initiate_shutdown(&self) {
+        if self.shutting_down.replace(true) {
+            return;
+        }
+        debug!(ctx = self.log_ctx(); "shutdown is requested");
+        for id in self.accept_fibers.borrow().iter() {
+            fiber::wakeup(*id);
+        }
+        self.wakeup_connections();
+    }
+
+    /// Force all of the remaining connections to close.
+    /// Returns amount of aborted connections.
+    fn abort(&self) -> usize {
+        self.aborted.set(true);
+        self.wakeup_connections();
+        self.connections.borrow().len()
+    }
+
+    pub(crate) fn active_connections(&self) -> usize {
+        self.connections.borrow().len()
+    }
+
+    /// Yield current fiber until some connection is finished or the fiber is woken up.
+    pub(crate) fn wait_connection_finished(&self) {
+        self.connection_finished.wait();
+    }
+
+    pub(crate) fn record_accept_failure(&self) {
+        self.accept_failures.set(self.accept_failures.get() + 1);
+    }
+
+    /// Wake up connection fibers waiting on their sockets.
+    ///
+    /// Connections being polled are skipped: their request handlers might be sleeping
+    /// or waiting on a condition, which must not be interrupted. Such connections check
+    /// the shutdown flags once the poll is over.
+    fn wakeup_connections(&self) {
+        for entry in self.connections.borrow().values() {
+            if let (Some(id), false) = (entry.fiber, entry.polling) {
+                fiber::wakeup(id);
+            }
+        }
+    }
+
+    fn notify_if_finished(&self) {
+        if self.is_finished() {
+            self.finished.broadcast();
+        }
+    }
+
+    fn log_ctx(&self) -> &str {
+        &self.server_name
+    }
+
+    /// Reserve a listener which is going to accept connections.
+    ///
+    /// Server is considered running until the returned guard is dropped, so `wait` wouldn't
+    /// return before the accept fiber is even started. The guard is meant to be moved
+    /// into the accept fiber, which calls [AcceptGuard::enter] once started.
+    pub(crate) fn reserve_accept(self: &Rc<Self>) -> AcceptGuard {
+        self.accepting.set(self.accepting.get() + 1);
+        AcceptGuard {
+            lifecycle: self.clone(),
+            fiber: None,
+        }
+    }
+
+    /// Register accepted connection, before its fiber is spawned.
+    ///
+    /// Connection is considered active until the returned guard is dropped. The guard
+    /// is meant to be moved into the connection fiber, which calls [ConnectionGuard::enter]
+    /// once started.
+    pub(crate) fn reserve_connection(self: &Rc<Self>) -> ConnectionGuard {
+        let id = self.next_connection_id.get();
+        self.next_connection_id.set(id.wrapping_add(1));
+        self.connections.borrow_mut().insert(
+            id,
+            ConnectionEntry {
+                fiber: None,
+                polling: false,
+            },
+        );
+        ConnectionGuard {
+            lifecycle: self.clone(),
+            id,
+        }
+    }
+}
+
+pub(crate) struct AcceptGuard {
+    lifecycle: Rc<Lifecycle>,
+    fiber: Option<FiberId>,
+}
+
+impl AcceptGuard {
+    /// Register current fiber as the accepting one, so it's woken up on shutdown.
+    pub(crate) fn enter(&mut self) {
+        let id = fiber::id();
+        self.lifecycle.accept_fibers.borrow_mut().push(id);
+        self.fiber = Some(id);
+    }
+}
+
+impl Drop for AcceptGuard {
+    fn drop(&mut self) {
+        if let Some(id) = self.fiber {
+            self.lifecycle
+                .accept_fibers
+                .borrow_mut()
+                .retain(|fiber| *fiber != id);
+        }
+        let accepting = &self.lifecycle.accepting;
+        accepting.set(accepting.get() - 1);
+        self.lifecycle.notify_if_finished();
+    }
+}
+
+struct ConnectionEntry {
+    /// Fiber serving the connection, unknown until it's started.
+    fiber: Option<FiberId>,
+    /// Whether the connection is being polled, see [Lifecycle::wakeup_connections].
+    polling: bool,
+}
+
+pub(crate) struct ConnectionGuard {
+    lifecycle: Rc<Lifecycle>,
+    id: u64,
+}
+
+impl ConnectionGuard {
+    /// Register current fiber as the one serving the connection, so it's woken up on shutdown.
+    pub(crate) fn enter(&self) {
+        self.with_entry(|entry| entry.fiber = Some(fiber::id()));
+    }
+
+    /// Poll the connection, shielding its fiber from the shutdown wakeups meanwhile.
+    pub(crate) fn poll<T>(&self, poll: impl FnOnce() -> T) -> T {
+        self.with_entry(|entry| entry.polling = true);
+        let result = poll();
+        self.with_entry(|entry| entry.polling = false);
+        result
+    }
+
+    fn with_entry(&self, f: impl FnOnce(&mut ConnectionEntry)) {
+        if let Some(entry) = self.lifecycle.connections.borrow_mut().get_mut(&self.id) {
+            f(entry);
+        }
+    }
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.lifecycle.connections.borrow_mut().remove(&self.id);
+        self.lifecycle.connection_finished.broadcast();
+        self.lifecycle.notify_if_finished();
+    }
+}
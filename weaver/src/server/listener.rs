@@ -0,0 +1,447 @@
+//! Listening sockets of the server.
+//!
+//! Sockets are created with std and awaited through coio, so TCP and Unix domain sockets
+//! share the same accept path. Accepted streams are handed to tarantool [TcpStream],
+//! which is able to drive any stream socket.
+use std::{
+    fmt::Display,
+    fs,
+    io::{self, ErrorKind},
+    net::{SocketAddr, TcpListener},
+    os::{
+        fd::{AsRawFd, IntoRawFd, OwnedFd, RawFd},
+        unix::{
+            fs::{FileTypeExt, PermissionsExt},
+            net::{UnixListener, UnixStream},
+        },
+    },
+    path::PathBuf,
+    time::Duration,
+};
+
+use derive_builder::Builder;
+use tarantool::{coio::coio_wait, ffi::tarantool::CoIOFlags, network::tcp::stream::TcpStream};
+
+use super::{BindParams, Error};
+
+/// Where the server accepts connections.
+#[derive(Debug, Clone)]
+pub enum Bind {
+    Tcp(BindParams),
+    Unix(UnixBindParams),
+}
+
+impl Default for Bind {
+    fn default() -> Self {
+        Self::Tcp(BindParams::default())
+    }
+}
+
+impl From<BindParams> for Bind {
+    fn from(params: BindParams) -> Self {
+        Self::Tcp(params)
+    }
+}
+
+impl From<UnixBindParams> for Bind {
+    fn from(params: UnixBindParams) -> Self {
+        Self::Unix(params)
+    }
+}
+
+impl Display for Bind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tcp(params) => write!(f, "{}:{}", params.host, params.port),
+            Self::Unix(params) => write!(f, "unix:{}", params.path.display()),
+        }
+    }
+}
+
+/// Additional address the server listens on, see [super::ServerConfig::listeners].
+#[derive(Debug, Clone, Builder)]
+pub struct ListenerConfig {
+    #[builder(setter(into))]
+    pub bind: Bind,
+    /// Listener name, used for logging and fiber names.
+    /// If not provided, server name with the bound address will be used.
+    #[builder(default)]
+    pub name: Option<String>,
+}
+
+impl From<Bind> for ListenerConfig {
+    fn from(bind: Bind) -> Self {
+        Self { bind, name: None }
+    }
+}
+
+impl From<BindParams> for ListenerConfig {
+    fn from(params: BindParams) -> Self {
+        Bind::from(params).into()
+    }
+}
+
+impl From<UnixBindParams> for ListenerConfig {
+    fn from(params: UnixBindParams) -> Self {
+        Bind::from(params).into()
+    }
+}
+
+/// Unix domain socket to listen on.
+#[derive(Debug, Clone, Builder)]
+pub struct UnixBindParams {
+    /// Path of the socket file.
+    #[builder(setter(into))]
+    pub path: PathBuf,
+    /// Permissions of the socket file, i.e. `0o660`.
+    /// If not provided, permissions are defined by the process umask.
+    #[builder(default)]
+    pub permissions: Option<u32>,
+    /// Remove the socket file left by a previous run before binding.
+    /// File is removed only if nobody listens on it.
+    #[builder(default = "true")]
+    pub remove_stale: bool,
+}
+
+impl UnixBindParams {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            permissions: None,
+            remove_stale: true,
+        }
+    }
+
+    fn remove_stale_socket(&self) -> io::Result<()> {
+        let metadata = match fs::symlink_metadata(&self.path) {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err),
+        };
+        if !metadata.file_type().is_socket() {
+            return Err(io::Error::new(
+                ErrorKind::AlreadyExists,
+                "path is occupied by a file which is not a socket",
+            ));
+        }
+        match UnixStream::connect(&self.path) {
+            Ok(_) => Err(io::Error::new(
+                ErrorKind::AddrInUse,
+                "socket is in use by another process",
+            )),
+            Err(err) if err.kind() == ErrorKind::ConnectionRefused => fs::remove_file(&self.path),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// How listeners react to failures of accepting connections.
+///
+/// Failures caused by a single connection, i.e. reset by the peer before it was accepted,
+/// are skipped right away. Running out of resources, i.e. file descriptors, is retried
+/// after a backoff, so the listener doesn't spin while the system recovers.
+#[derive(Debug, Clone, Builder)]
+#[builder(default)]
+pub struct AcceptPolicy {
+    /// Delay before the next attempt after the first failure.
+    pub backoff: Duration,
+    /// Backoff is doubled on every consecutive failure up to this value.
+    pub max_backoff: Duration,
+    /// Stop the listener on unexpected errors instead of retrying them with backoff.
+    pub stop_on_unexpected: bool,
+}
+
+impl Default for AcceptPolicy {
+    fn default() -> Self {
+        Self {
+            backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_secs(1),
+            stop_on_unexpected: false,
+        }
+    }
+}
+
+impl AcceptPolicy {
+    /// Delay before the next attempt, given the delay of the previous consecutive failure.
+    pub(crate) fn next_backoff(&self, previous: Option<Duration>) -> Duration {
+        previous
+            .map(|previous| previous.saturating_mul(2))
+            .unwrap_or(self.backoff)
+            .min(self.max_backoff)
+    }
+}
+
+/// Class of `accept` failure, see [AcceptPolicy].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AcceptErrorKind {
+    /// Pending connection failed, next one could be accepted right away.
+    Connection,
+    /// System is out of resources needed for a new connection.
+    Resources,
+    Unexpected,
+}
+
+impl AcceptErrorKind {
+    pub(crate) fn of(err: &io::Error) -> Self {
+        match err.raw_os_error() {
+            // Linux reports network errors of the pending connection from `accept`.
+            Some(
+                libc::ECONNABORTED
+                | libc::ECONNRESET
+                | libc::EPROTO
+                | libc::EPERM
+                | libc::ENETDOWN
+                | libc::ENETUNREACH
+                | libc::EHOSTDOWN
+                | libc::EHOSTUNREACH
+                | libc::ENOPROTOOPT
+                | libc::EOPNOTSUPP
+                | libc::ETIMEDOUT,
+            ) => Self::Connection,
+            Some(libc::EMFILE | libc::ENFILE | libc::ENOBUFS | libc::ENOMEM) => Self::Resources,
+            _ => Self::Unexpected,
+        }
+    }
+}
+
+/// Address the server actually listens on.
+///
+/// Differs from the configured [Bind] when port `0` is requested: the port picked
+/// by the system is reported here.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LocalAddr {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+}
+
+impl LocalAddr {
+    /// TCP address, if the listener is a TCP one.
+    pub fn tcp(&self) -> Option<SocketAddr> {
+        match self {
+            Self::Tcp(addr) => Some(*addr),
+            Self::Unix(_) => None,
+        }
+    }
+}
+
+impl Display for LocalAddr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "{addr}"),
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+/// Socket accepting connections.
+pub(crate) enum Listener {
+    Tcp(TcpListener),
+    Unix(UnixListener, PathBuf),
+}
+
+impl Listener {
+    pub(crate) fn bind(bind: &Bind) -> Result<Self, Error> {
+        let map_err =
+            |err: io::Error| Error::InitFailed(format!("failed to bind to {bind}: {err}"));
+        let listener = match bind {
+            Bind::Tcp(params) => {
+                Self::Tcp(TcpListener::bind((params.host.as_str(), params.port)).map_err(map_err)?)
+            }
+            Bind::Unix(params) => {
+                if params.remove_stale {
+                    params.remove_stale_socket().map_err(map_err)?;
+                }
+                let listener = UnixListener::bind(&params.path).map_err(map_err)?;
+                if let Some(mode) = params.permissions {
+                    fs::set_permissions(&params.path, fs::Permissions::from_mode(mode))
+                        .map_err(map_err)?;
+                }
+                Self::Unix(listener, params.path.clone())
+            }
+        };
+        match &listener {
+            Self::Tcp(listener) => listener.set_nonblocking(true),
+            Self::Unix(listener, _) => listener.set_nonblocking(true),
+        }
+        .map_err(map_err)?;
+        Ok(listener)
+    }
+
+    pub(crate) fn local_addr(&self) -> Result<LocalAddr, Error> {
+        match self {
+            Self::Tcp(listener) => listener
+                .local_addr()
+                .map(LocalAddr::Tcp)
+                .map_err(|err| Error::InitFailed(format!("failed to get listener address: {err}"))),
+            Self::Unix(_, path) => Ok(LocalAddr::Unix(path.clone())),
+        }
+    }
+
+    /// Accept new connection.
+    ///
+    /// Yields current fiber until a connection is ready. If the fiber is woken up before that,
+    /// `None` is returned so the caller could recheck its state.
+    pub(crate) fn accept(&self) -> io::Result<Option<Accepted>> {
+        loop {
+            match self.try_accept() {
+                Ok(fd) => return Ok(Some(Accepted(fd))),
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) if err.kind() == ErrorKind::WouldBlock => {
+                    let timeout = Duration::MAX.as_secs_f64();
+                    if coio_wait(self.as_raw_fd(), CoIOFlags::READ, timeout).is_err() {
+                        return Ok(None);
+                    }
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Accept connection without blocking, returns nonblocking socket.
+    fn try_accept(&self) -> io::Result<OwnedFd> {
+        match self {
+            Self::Tcp(listener) => {
+                let (stream, _) = listener.accept()?;
+                stream.set_nonblocking(true)?;
+                stream.set_nodelay(true)?;
+                Ok(stream.into())
+            }
+            Self::Unix(listener, _) => {
+                let (stream, _) = listener.accept()?;
+                stream.set_nonblocking(true)?;
+                Ok(stream.into())
+            }
+        }
+    }
+}
+
+/// Nonblocking socket of the accepted connection, closed on drop unless it's served.
+#[derive(Debug)]
+pub(crate) struct Accepted(OwnedFd);
+
+impl Accepted {
+    /// Hand the connection over to be served asynchronously.
+    pub(crate) fn into_stream(self) -> TcpStream {
+        TcpStream::from(self.0.into_raw_fd())
+    }
+
+    /// Send `response` and close the connection without yielding.
+    ///
+    /// Whatever doesn't fit into the socket buffer right away is dropped, so the response
+    /// is expected to be short. Data already sent by the peer is discarded, so the peer
+    /// reads the response instead of getting the connection reset.
+    pub(crate) fn reject(self, response: &[u8]) -> io::Result<()> {
+        let fd = self.0.as_raw_fd();
+        // SAFETY: the buffer is valid for the duration of the call.
+        let sent = unsafe {
+            libc::send(
+                fd,
+                response.as_ptr().cast(),
+                response.len(),
+                libc::MSG_NOSIGNAL,
+            )
+        };
+        if sent < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: the descriptor is owned by `self`.
+        unsafe { libc::shutdown(fd, libc::SHUT_WR) };
+        let mut buf = [0u8; 1024];
+        loop {
+            // SAFETY: the buffer is valid for the duration of the call.
+            let read =
+                unsafe { libc::recv(fd, buf.as_mut_ptr().cast(), buf.len(), libc::MSG_DONTWAIT) };
+            if read <= 0 {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl AsRawFd for Listener {
+    fn as_raw_fd(&self) -> RawFd {
+        match self {
+            Self::Tcp(listener) => listener.as_raw_fd(),
+            Self::Unix(listener, _) => listener.as_raw_fd(),
+        }
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        if let Self::Unix(_, path) = self {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn os_error(code: i32) -> io::Error {
+        io::Error::from_raw_os_error(code)
+    }
+
+    #[test]
+    fn accept_error_kind() {
+        for code in [
+            libc::ECONNABORTED,
+            libc::ECONNRESET,
+            libc::EPROTO,
+            libc::ETIMEDOUT,
+        ] {
+            assert_eq!(
+                AcceptErrorKind::of(&os_error(code)),
+                AcceptErrorKind::Connection
+            );
+        }
+        for code in [libc::EMFILE, libc::ENFILE, libc::ENOBUFS, libc::ENOMEM] {
+            assert_eq!(
+                AcceptErrorKind::of(&os_error(code)),
+                AcceptErrorKind::Resources
+            );
+        }
+        assert_eq!(
+            AcceptErrorKind::of(&os_error(libc::EBADF)),
+            AcceptErrorKind::Unexpected
+        );
+        assert_eq!(
+            AcceptErrorKind::of(&io::Error::new(ErrorKind::Other, "no errno")),
+            AcceptErrorKind::Unexpected
+        );
+    }
+
+    #[test]
+    fn backoff_is_doubled_up_to_max() {
+        let policy = AcceptPolicy {
+            backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(50),
+            stop_on_unexpected: false,
+        };
+        let mut previous = None;
+        let delays: Vec<_> = (0..5)
+            .map(|_| {
+                let delay = policy.next_backoff(previous);
+                previous = Some(delay);
+                delay.as_millis()
+            })
+            .collect();
+        assert_eq!(delays, [10, 20, 40, 50, 50]);
+    }
+
+    #[test]
+    fn backoff_never_exceeds_max() {
+        let policy = AcceptPolicy {
+            backoff: Duration::from_secs(5),
+            max_backoff: Duration::from_secs(1),
+            stop_on_unexpected: false,
+        };
+        assert_eq!(policy.next_backoff(None), Duration::from_secs(1));
+        assert_eq!(
+            policy.next_backoff(Some(Duration::MAX)),
+            Duration::from_secs(1)
+        );
+    }
+}
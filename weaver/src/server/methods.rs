@@ -0,0 +1,74 @@
+//! Methods registered for the matched path.
+//!
+//! `HEAD` and `OPTIONS` are served automatically for every path, unless registered explicitly:
+//! `HEAD` by the `GET` handler with the body dropped, `OPTIONS` with the allowed methods.
+use http::{header, HeaderValue, Method, StatusCode};
+
+use super::{Body, Response};
+
+/// Methods the matched path is registered for, including the automatically served ones.
+///
+/// Passed in request extensions to the handler set with [super::Server::method_not_allowed].
+/// Default response carries them in the `Allow` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AllowedMethods(Vec<Method>);
+
+impl AllowedMethods {
+    pub(crate) fn new<'a>(registered: impl IntoIterator<Item = &'a Method>) -> Self {
+        let mut methods: Vec<Method> = registered.into_iter().cloned().collect();
+        if methods.contains(&Method::GET) && !methods.contains(&Method::HEAD) {
+            methods.push(Method::HEAD);
+        }
+        if !methods.contains(&Method::OPTIONS) {
+            methods.push(Method::OPTIONS);
+        }
+        Self(methods)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Method> {
+        self.0.iter()
+    }
+
+    pub fn contains(&self, method: &Method) -> bool {
+        self.0.contains(method)
+    }
+
+    /// Value of the `Allow` header, i.e. `GET, POST`.
+    pub fn header_value(&self) -> HeaderValue {
+        let value = self
+            .0
+            .iter()
+            .map(Method::as_str)
+            .collect::<Vec<_>>()
+            .join(", ");
+        HeaderValue::try_from(value).expect("method names are valid header values")
+    }
+
+    /// Set the `Allow` header on the response, unless it's set already.
+    pub(crate) fn apply(&self, response: &mut Response) {
+        response
+            .headers_mut()
+            .entry(header::ALLOW)
+            .or_insert_with(|| self.header_value());
+    }
+}
+
+/// Answer to `OPTIONS` request for the path without explicit handler.
+pub(crate) fn options_response(allowed: &AllowedMethods) -> Response {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = StatusCode::NO_CONTENT;
+    allowed.apply(&mut response);
+    response
+}
+
+/// Drop the body of `GET` response served for `HEAD` request.
+///
+/// `Content-Length` is kept, so it matches the one `GET` request would receive.
+pub(crate) fn strip_body(response: &mut Response) {
+    let body = std::mem::take(response.body_mut());
+    let length = body.data.map_or(0, |data| data.len());
+    response
+        .headers_mut()
+        .entry(header::CONTENT_LENGTH)
+        .or_insert_with(|| HeaderValue::from(length));
+}
@@ -1,10 +1,13 @@
 use std::{
+    cell::Cell,
     collections::HashMap,
-    future::Future,
+    future::{poll_fn, Future},
+    io,
     ops::{Deref, DerefMut},
-    pin::Pin,
+    pin::{pin, Pin},
     rc::Rc,
     task::{Context, Poll},
+    time::Duration,
 };
 
 use derive_builder::Builder;
@@ -13,27 +16,87 @@ use hyper::{
     service::service_fn,
     Request as HyperRequest, Response as HyperResponse,
 };
-use log::{debug, error, info, trace};
+use hyper_util::server::conn::auto;
+use log::{debug, error, info, trace, warn};
 use matchit::Router;
-use tarantool::{
-    fiber::{self},
-    network::tcp::{listener::TcpListener, stream::TcpStream},
-};
+use percent_encoding::percent_decode_str;
+use tarantool::{fiber, network::tcp::stream::TcpStream};
 
 use crate::{
-    runtime::{TarantoolAsyncIO, TarantoolHyperExecutor},
+    runtime::{TarantoolAsyncIO, TarantoolHyperExecutor, TarantoolHyperTimer, TarantoolSleep},
     utils::SmallMap,
 };
+use futures_io::{AsyncRead, AsyncWrite};
+use handle::{ConnectionGuard, Lifecycle};
 use http::StatusCode;
+use listener::{AcceptErrorKind, Accepted, Listener};
+
+mod body;
+mod handle;
+mod listener;
+mod methods;
+mod path;
+mod protocol;
+mod state;
+#[cfg(feature = "tls")]
+pub mod tls;
+
+pub use body::{BodyError, BodyLimit, RequestBody};
+pub use handle::ServerHandle;
+pub use listener::{
+    AcceptPolicy, AcceptPolicyBuilder, Bind, ListenerConfig, ListenerConfigBuilder, LocalAddr,
+    UnixBindParams, UnixBindParamsBuilder,
+};
+#[cfg(feature = "frontend")]
+pub(crate) use methods::strip_body;
+pub use methods::AllowedMethods;
+pub use path::{PathNormalization, PathNormalizationBuilder, TrailingSlash};
+pub use protocol::{Http1Config, Http1ConfigBuilder, Http2Config, Http2ConfigBuilder, Protocol};
+pub(crate) use state::RequestStates;
+pub use state::{StateMap, StateType};
 
 #[derive(Debug, Clone, Builder, Default)]
 pub struct ServerConfig {
-    #[builder(default)]
-    pub bind: BindParams,
+    /// Address to listen on: TCP host and port or Unix domain socket path.
+    #[builder(default, setter(into))]
+    pub bind: Bind,
     /// Server name, used for logging and fiber name.
-    /// If not provided, default name with host and port will be used.
+    /// If not provided, default name with the bound address will be used.
     #[builder(default)]
     pub name: Option<String>,
+    /// Additional addresses to listen on, served by the same router.
+    /// Each listener accepts connections in its own fiber.
+    #[builder(default, setter(each(name = "listener", into)))]
+    pub listeners: Vec<ListenerConfig>,
+    /// Reaction of the listeners to failures of accepting connections.
+    #[builder(default)]
+    pub accept_policy: AcceptPolicy,
+    /// Timeouts of the served connections.
+    #[builder(default)]
+    pub timeouts: Timeouts,
+    /// Cap on the amount of simultaneously served connections, unlimited by default.
+    #[builder(default)]
+    pub connection_limit: Option<ConnectionLimit>,
+    /// HTTP versions served, detected automatically by default.
+    #[builder(default)]
+    pub protocol: Protocol,
+    #[builder(default)]
+    pub http1: Http1Config,
+    #[builder(default)]
+    pub http2: Http2Config,
+    /// Maximum size of request bodies, 2 MiB by default.
+    /// Could be overridden per route or group.
+    #[builder(default)]
+    pub body_limit: BodyLimit,
+    /// Normalization of the request paths before routing.
+    /// Duplicate slashes are collapsed and dot segments are resolved by default,
+    /// trailing slash is significant.
+    #[builder(default)]
+    pub path_normalization: PathNormalization,
+    /// TLS settings. If provided, connections are served over TLS only.
+    #[cfg(feature = "tls")]
+    #[builder(default)]
+    pub tls: Option<tls::TlsConfig>,
 }
 
 #[derive(Debug, Clone, Builder)]
@@ -51,23 +114,113 @@ impl Default for BindParams {
     }
 }
 
+/// Timeouts protecting the server from idle and slow clients.
+#[derive(Debug, Clone, Builder)]
+#[builder(default)]
+pub struct Timeouts {
+    /// Time given to the client to send request headers, HTTP/1 only.
+    /// Includes waiting for the next request on a keep-alive connection.
+    /// Defaults to 30 seconds, `None` disables the timeout.
+    pub header_read: Option<Duration>,
+    /// Connection without requests in progress is closed after this time.
+    /// Defaults to 60 seconds, `None` keeps idle connections open.
+    pub keep_alive: Option<Duration>,
+    /// Interval of HTTP/2 keep-alive pings. Disabled by default.
+    pub http2_ping_interval: Option<Duration>,
+    /// Connection is closed if HTTP/2 ping is not acknowledged within this time.
+    /// Has no effect if [Self::http2_ping_interval] is disabled. Defaults to 20 seconds.
+    pub http2_ping_timeout: Duration,
+    /// Time given to the client to complete TLS handshake, has effect on TLS servers only.
+    /// Defaults to 10 seconds, `None` disables the timeout.
+    pub tls_handshake: Option<Duration>,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            header_read: Some(Duration::from_secs(30)),
+            keep_alive: Some(Duration::from_secs(60)),
+            http2_ping_interval: None,
+            http2_ping_timeout: Duration::from_secs(20),
+            tls_handshake: Some(Duration::from_secs(10)),
+        }
+    }
+}
+
+/// Cap on the amount of simultaneously served connections, shared by all of the listeners.
+///
+/// Every connection is served by its own fiber, so the cap bounds the amount of fibers
+/// competing with the rest of the instance. Connection is counted from the moment it's
+/// accepted, even before its fiber is started.
+#[derive(Debug, Clone)]
+pub struct ConnectionLimit {
+    pub max: usize,
+    /// What happens to the new connections when the cap is reached.
+    pub on_limit: OnConnectionLimit,
+}
+
+impl ConnectionLimit {
+    /// Stop accepting connections until some of the served ones are finished.
+    /// Pending connections are queued by the system meanwhile.
+    pub fn pause(max: usize) -> Self {
+        Self {
+            max,
+            on_limit: OnConnectionLimit::Pause,
+        }
+    }
+
+    /// Keep accepting connections, but answer them with `503 Service Unavailable`
+    /// right away and close them, without reading their requests.
+    /// Connections over TLS are closed without a response.
+    pub fn reject(max: usize, retry_after: Duration) -> Self {
+        Self {
+            max,
+            on_limit: OnConnectionLimit::Reject { retry_after },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum OnConnectionLimit {
+    Pause,
+    /// `retry_after` is sent to the client in `Retry-After` header, rounded to seconds.
+    Reject {
+        retry_after: Duration,
+    },
+}
+
 #[derive(Default, Clone)]
 pub struct Server {
     cfg: ServerConfig,
     name: String,
     router: InnerRouter,
+    fallbacks: Vec<Fallback>,
+    method_not_allowed: Option<SharedRequestHandler>,
+    layers: Vec<Layer>,
+    outer_layer: Option<Layer>,
+    pre_routing: Vec<PreRoutingHook>,
+    states: StateMap,
+    /// States required by the registered handlers, with the handler description.
+    required_states: Vec<(StateType, String)>,
 }
 
 impl Server {
     pub fn new(cfg: ServerConfig) -> Self {
-        let name = cfg
-            .name
-            .clone()
-            .unwrap_or_else(|| format!("weaver_http_server_{}_{}", cfg.bind.host, cfg.bind.port));
+        let name = cfg.name.clone().unwrap_or_else(|| match &cfg.bind {
+            Bind::Tcp(bind) => format!("weaver_http_server_{}_{}", bind.host, bind.port),
+            Bind::Unix(bind) => format!("weaver_http_server_unix_{}", bind.path.display()),
+        });
         Self {
             cfg,
             name,
             router: Router::new(),
+            fallbacks: Vec::new(),
+            method_not_allowed: None,
+            layers: Vec::new(),
+            outer_layer: None,
+            pre_routing: Vec::new(),
+            states: StateMap::default(),
+            required_states: Vec::new(),
         }
     }
 
@@ -78,9 +231,13 @@ impl Server {
         route: Route,
         handler: impl Into<SharedRequestHandler>,
     ) -> Result<&mut Self, Error> {
-        let handler = handler.into();
+        let endpoint = Endpoint {
+            handler: handler.into(),
+            body_limit: route.body_limit,
+        };
+        let required_states = endpoint.handler.as_handler().required_states();
         let mut bucket = self.router.remove(&route.path).unwrap_or_default();
-        let existing = bucket.insert(route.method.clone(), handler);
+        let existing = bucket.insert(route.method.clone(), endpoint);
 
         if existing.is_some() {
             return Err(Error::RouteOccupied {
@@ -97,63 +254,246 @@ impl Server {
             })?;
 
         debug!(ctx = self.log_ctx(); "registering handler for path: {route:?}");
+        self.require_states(required_states, || {
+            format!("route {} {}", route.method, route.path)
+        });
 
         Ok(self)
     }
 
-    pub fn defer(self) -> Result<(), Error> {
-        self.into_fiber()
+    /// Register handler for requests which didn't match any route.
+    ///
+    /// Replaces previously registered fallback, if any.
+    /// Fallbacks registered with [crate::frontend::routing::Group::fallback] take precedence
+    /// for the paths under their group prefix.
+    pub fn fallback(&mut self, handler: impl Into<SharedRequestHandler>) -> &mut Self {
+        self.fallback_at("/", handler)
+    }
+
+    /// Register handler for requests which matched the route path, but not its methods.
+    ///
+    /// Replaces previously registered handler, if any.
+    /// Methods registered for the path are passed in [AllowedMethods] request extension.
+    /// `Allow` header is added to the response, unless the handler sets it.
+    pub fn method_not_allowed(&mut self, handler: impl Into<SharedRequestHandler>) -> &mut Self {
+        debug!(ctx = self.log_ctx(); "registering method not allowed handler");
+        let handler = handler.into();
+        self.require_states(handler.as_handler().required_states(), || {
+            "method not allowed handler".into()
+        });
+        self.method_not_allowed = Some(handler);
+        self
+    }
+
+    /// Share the state with all the handlers of the server.
+    ///
+    /// Handlers access it with [crate::frontend::request::state::State] extractor.
+    /// State is stored once and cloned for every extraction, wrap it into [Rc] if it's
+    /// expensive to clone. States are distinguished by type, the same type replaces
+    /// previously registered state.
+    ///
+    /// Every state required by the handlers must be registered, otherwise the server
+    /// fails to start.
+    pub fn with_state<T: 'static>(&mut self, state: T) -> &mut Self {
+        self.states.insert(state);
+        self
+    }
+
+    /// Remember states required by the handler, to check them once the server is built.
+    pub(crate) fn require_states(
+        &mut self,
+        states: Vec<StateType>,
+        required_by: impl Fn() -> String,
+    ) {
+        for state in states {
+            self.required_states.push((state, required_by()));
+        }
+    }
+
+    /// Wrap the whole request dispatch with the layer.
+    ///
+    /// Layer receives the handler of the server, including routing failures and fallbacks,
+    /// and returns the wrapped one. First registered layer is the outermost one.
+    pub fn layer(
+        &mut self,
+        layer: impl Fn(SharedRequestHandler) -> SharedRequestHandler + 'static,
+    ) -> &mut Self {
+        self.layers.push(Rc::new(layer));
+        self
+    }
+
+    /// Wrap the whole request dispatch with the layer, outside of all the other layers.
+    /// Replaces previously set outer layer, if any.
+    #[cfg(feature = "frontend")]
+    pub(crate) fn outer_layer(
+        &mut self,
+        layer: impl Fn(SharedRequestHandler) -> SharedRequestHandler + 'static,
+    ) -> &mut Self {
+        self.outer_layer = Some(Rc::new(layer));
+        self
+    }
+
+    /// Register hook rewriting requests before they are routed.
+    ///
+    /// Hooks may change URI, method and headers of the request, i.e. strip the path prefix
+    /// added by the proxy, and are called in the order of registration.
+    /// Server middlewares are called before the hooks, so they observe the original request.
+    /// Redirects to the normalized path point to the original path as well.
+    pub fn pre_routing(&mut self, hook: impl Fn(&mut Request) + 'static) -> &mut Self {
+        self.pre_routing.push(Rc::new(hook));
+        self
+    }
+
+    /// Register fallback for requests under the `prefix` path which didn't match any route.
+    pub(crate) fn fallback_at(
+        &mut self,
+        prefix: &str,
+        handler: impl Into<SharedRequestHandler>,
+    ) -> &mut Self {
+        let prefix = prefix.trim_end_matches('/').to_string();
+        debug!(ctx = self.log_ctx(); "registering fallback for prefix: {prefix:?}");
+        let handler = handler.into();
+        self.require_states(handler.as_handler().required_states(), || {
+            format!("fallback {prefix}/")
+        });
+        self.fallbacks.retain(|fallback| fallback.prefix != prefix);
+        self.fallbacks.push(Fallback { prefix, handler });
+        self
+    }
+
+    /// Start the server in a separate non-joinable fiber.
+    ///
+    /// Addresses are bound before the fiber is started, so bind failures are returned here.
+    /// Returned handle is used to stop the server and to get its bound addresses.
+    pub fn defer(self) -> Result<ServerHandle, Error> {
+        let (handle, fiber) = self.into_fiber()?;
+        fiber
             .defer_non_joinable()
             .map_err(|err| Error::InitFailed(format!("failed to create main fiber: {err}")))?;
 
-        Ok(())
+        Ok(handle)
     }
 
-    pub fn into_fiber(self) -> fiber::Builder<impl FnOnce() -> std::result::Result<(), Error>> {
-        let bind = self.cfg.bind;
+    /// Bind the addresses and convert the server into the fiber accepting connections.
+    ///
+    /// Fiber is not started, it's up to the caller how to run it.
+    /// Returned handle is used to stop the server once the fiber is running.
+    #[allow(clippy::type_complexity)]
+    pub fn into_fiber(
+        self,
+    ) -> Result<
+        (
+            ServerHandle,
+            fiber::Builder<impl FnOnce() -> std::result::Result<(), Error>>,
+        ),
+        Error,
+    > {
+        if let Some((state, required_by)) = self
+            .required_states
+            .iter()
+            .find(|(state, _)| !self.states.contains(*state))
+        {
+            return Err(Error::MissingState {
+                state: state.name(),
+                required_by: required_by.clone(),
+            });
+        }
+        let mut states = RequestStates::default();
+        states.push(self.states);
+
+        let cfg = self.cfg;
         let fiber_name = self.name;
+        #[cfg(feature = "tls")]
+        let tls = cfg
+            .tls
+            .as_ref()
+            .map(|tls| tls.acceptor(cfg.protocol))
+            .transpose()?;
+        let builder = connection_builder(&cfg, &fiber_name)?;
+
+        // Every address is bound before accepting anything,
+        // so misconfigured server doesn't serve partially.
+        let mut listeners = Vec::with_capacity(cfg.listeners.len() + 1);
+        listeners.push((fiber_name.clone(), Listener::bind(&cfg.bind)?));
+        for listener_cfg in &cfg.listeners {
+            let name = listener_cfg
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("{fiber_name}_{}", listener_cfg.bind));
+            listeners.push((name, Listener::bind(&listener_cfg.bind)?));
+        }
+        let local_addrs = listeners
+            .iter()
+            .map(|(_, listener)| listener.local_addr())
+            .collect::<Result<Vec<_>, _>>()?;
+        for addr in &local_addrs {
+            info!(ctx = fiber_name.as_str(); "Server bind to address {addr} successfully");
+        }
+
+        let dispatcher = SharedRequestHandler::new(Dispatcher {
+            router: self.router,
+            fallbacks: self.fallbacks,
+            method_not_allowed: self.method_not_allowed,
+            pre_routing: self.pre_routing,
+            path_normalization: cfg.path_normalization,
+            body_limit: cfg.body_limit,
+            server_name: fiber_name.clone(),
+        });
+        // First registered layer is the outermost one.
+        let handler = self
+            .layers
+            .iter()
+            .rev()
+            .fold(dispatcher, |handler, layer| layer(handler));
+        let handler = match &self.outer_layer {
+            Some(layer) => layer(handler),
+            None => handler,
+        };
 
+        let lifecycle = Rc::new(Lifecycle::new(&fiber_name, local_addrs));
         let processor = ServerProcessor {
             state: Rc::new(ServerState {
-                router: self.router,
+                handler,
                 server_name: fiber_name.clone(),
+                lifecycle: lifecycle.clone(),
+                accept_policy: cfg.accept_policy,
+                builder,
+                timeouts: cfg.timeouts,
+                connection_limit: cfg.connection_limit,
+                body_limit: cfg.body_limit,
+                states,
+                #[cfg(feature = "tls")]
+                tls,
             }),
         };
 
-        fiber::Builder::new()
-            .name(&fiber_name)
-            .func_async(async move {
-                let listener = TcpListener::bind(&bind.host, bind.port).map_err(|err| {
-                    Error::InitFailed(format!("failed to bind to needed address: {err}"))
-                })?;
-                info!(
-                    "Server bind to address {}:{} successfully",
-                    bind.host, bind.port
-                );
-
-                loop {
-                    let stream = listener
-                        .accept()
-                        .await
-                        .map_err(|err| Error::ConnectionError(err.to_string()))?;
-
-                    debug!("Server accepted new connection");
-                    let processor = processor.clone();
-                    fiber::Builder::new()
-                        .name(&fiber_name)
-                        .func_async(async move {
-                            if let Err(err) = processor.process_single_stream(stream).await {
-                                error!("Failure during single connection stream processing: {err}")
-                            }
-                        })
-                        .defer_non_joinable()
-                        .map_err(|err| {
-                            Error::ConnectionError(format!(
-                                "unable to spawn fiber for connection handle: {err}"
-                            ))
-                        })?;
-                }
-            })
+        let mut accept = lifecycle.reserve_accept();
+        let fiber = fiber::Builder::new().name(&fiber_name).func(move || {
+            accept.enter();
+            let mut listeners = listeners.into_iter();
+            let (name, listener) = listeners.next().expect("main listener is always bound");
+
+            for (name, listener) in listeners {
+                let mut accept = processor.state.lifecycle.reserve_accept();
+                let processor = processor.clone();
+                fiber::Builder::new()
+                    .name(&name)
+                    .func(move || {
+                        accept.enter();
+                        if let Err(err) = processor.accept_loop(&listener, &name) {
+                            error!(ctx = name.as_str(); "Listener exited with error: {err}");
+                        }
+                    })
+                    .defer_non_joinable()
+                    .map_err(|err| {
+                        Error::InitFailed(format!("failed to create listener fiber: {err}"))
+                    })?;
+            }
+
+            processor.accept_loop(&listener, &name)
+        });
+
+        Ok((ServerHandle::new(lifecycle), fiber))
     }
 
     /// Take ownership of the current server instance.
@@ -177,13 +517,44 @@ const STANDARD_METHODS_AMOUNT: usize = 9;
 ///
 /// Buckets amount is preallocated for storing them inline.
 /// Constant is picked specifically to cover all standard methods.
-type InnerRouter = Router<SmallMap<http::Method, SharedRequestHandler, STANDARD_METHODS_AMOUNT>>;
+type InnerRouter = Router<SmallMap<http::Method, Endpoint, STANDARD_METHODS_AMOUNT>>;
+
+/// Wrapper of the request handler applied to the whole server dispatch.
+type Layer = Rc<dyn Fn(SharedRequestHandler) -> SharedRequestHandler>;
+
+/// Rewrite of the request applied before routing.
+type PreRoutingHook = Rc<dyn Fn(&mut Request)>;
+
+/// Handler of the requests which didn't match any route under the path prefix.
+#[derive(Clone)]
+struct Fallback {
+    /// Prefix without trailing slash, empty for the root one.
+    prefix: String,
+    handler: SharedRequestHandler,
+}
+
+impl Fallback {
+    fn matches(&self, path: &str) -> bool {
+        path.strip_prefix(&self.prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    }
+}
+
+/// Registered handler along with its route settings.
+#[derive(Clone)]
+struct Endpoint {
+    handler: SharedRequestHandler,
+    body_limit: Option<BodyLimit>,
+}
 
 #[derive(Clone, Default, Builder, Debug)]
 pub struct Route {
     pub path: String,
     #[builder(default)]
     pub method: http::Method,
+    /// Overrides the server [ServerConfig::body_limit] for this route.
+    #[builder(default)]
+    pub body_limit: Option<BodyLimit>,
 }
 
 impl Route {
@@ -191,9 +562,15 @@ impl Route {
         Self {
             path: path.into(),
             method,
+            body_limit: None,
         }
     }
 
+    pub fn body_limit(mut self, limit: BodyLimit) -> Self {
+        self.body_limit = Some(limit);
+        self
+    }
+
     pub fn path(mut self, path: impl Into<String>) -> Self {
         self.path = path.into();
         self
@@ -286,101 +663,650 @@ struct ServerProcessor {
 }
 
 impl ServerProcessor {
-    async fn process_single_stream(&self, stream: TcpStream) -> Result<(), Error> {
-        let io = TarantoolAsyncIO::new(stream);
+    /// Accept connections from the listener until the server is shut down.
+    ///
+    /// Each connection is served in a separate fiber, named after the listener.
+    fn accept_loop(&self, listener: &Listener, name: &str) -> Result<(), Error> {
+        let lifecycle = &self.state.lifecycle;
+        let policy = &self.state.accept_policy;
+        let mut backoff = None;
+        loop {
+            if lifecycle.is_shutting_down() {
+                info!(ctx = name; "Server stopped accepting new connections");
+                return Ok(());
+            }
+            if let Some(limit) = &self.state.connection_limit {
+                if matches!(limit.on_limit, OnConnectionLimit::Pause)
+                    && lifecycle.active_connections() >= limit.max
+                {
+                    debug!(ctx = name; "Connection limit is reached, accepting is paused");
+                    // Woken up by a finished connection or the shutdown.
+                    lifecycle.wait_connection_finished();
+                    continue;
+                }
+            }
+            let stream = match listener.accept() {
+                Ok(Some(stream)) => stream,
+                // Shutdown wakes this fiber up, so the flag is rechecked even if
+                // the listener is still waiting for a connection.
+                Ok(None) => continue,
+                Err(err) => {
+                    lifecycle.record_accept_failure();
+                    match AcceptErrorKind::of(&err) {
+                        AcceptErrorKind::Connection => {
+                            debug!(ctx = name; "Connection failed before being accepted: {err}");
+                            continue;
+                        }
+                        AcceptErrorKind::Unexpected if policy.stop_on_unexpected => {
+                            return Err(Error::ConnectionError(format!(
+                                "failed to accept connection: {err}"
+                            )));
+                        }
+                        kind => {
+                            let delay = policy.next_backoff(backoff);
+                            backoff = Some(delay);
+                            if kind == AcceptErrorKind::Resources {
+                                warn!(ctx = name; "Failed to accept connection, retrying in {delay:?}: {err}");
+                            } else {
+                                error!(ctx = name; "Failed to accept connection, retrying in {delay:?}: {err}");
+                            }
+                            // Shutdown interrupts the sleep as well.
+                            fiber::sleep(delay);
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            debug!(ctx = name; "Server accepted new connection");
+            if let Some(retry_after) = self.rejection() {
+                debug!(ctx = name; "Connection limit is reached, connection is rejected");
+                if let Err(err) = self.reject(stream, retry_after) {
+                    debug!(ctx = name; "Failed to answer rejected connection: {err}");
+                }
+                continue;
+            }
+            let stream = stream.into_stream();
+            // Registered right away, so the connection is accounted even if
+            // its fiber is not started yet.
+            let connection = lifecycle.reserve_connection();
+            let processor = self.clone();
+            let connection_name = name.to_string();
+            let spawned = fiber::Builder::new()
+                .name(name)
+                .func_async(async move {
+                    connection.enter();
+                    if let Err(err) = processor
+                        .process_single_stream(stream, &connection)
+                        .await
+                    {
+                        error!(ctx = connection_name.as_str(); "Failure during single connection stream processing: {err}")
+                    }
+                })
+                .defer_non_joinable();
+            match spawned {
+                Ok(_) => backoff = None,
+                Err(err) => {
+                    // Connection is closed and unregistered along with the dropped fiber function.
+                    lifecycle.record_accept_failure();
+                    let delay = policy.next_backoff(backoff);
+                    backoff = Some(delay);
+                    warn!(ctx = name; "Unable to spawn fiber for connection, retrying in {delay:?}: {err}");
+                    fiber::sleep(delay);
+                }
+            }
+        }
+    }
+
+    /// `Retry-After` to reject new connection with, if [ConnectionLimit] says so.
+    fn rejection(&self) -> Option<Duration> {
+        match &self.state.connection_limit {
+            Some(ConnectionLimit {
+                max,
+                on_limit: OnConnectionLimit::Reject { retry_after },
+            }) if self.state.lifecycle.active_connections() >= *max => Some(*retry_after),
+            _ => None,
+        }
+    }
+
+    /// Answer connection over the limit right from the accept fiber, so it takes
+    /// neither a slot nor a fiber.
+    ///
+    /// TLS connections are closed without a response, as it can't be sent before the handshake.
+    fn reject(&self, stream: Accepted, retry_after: Duration) -> io::Result<()> {
+        #[cfg(feature = "tls")]
+        if self.state.tls.is_some() {
+            return Ok(());
+        }
+        let response = format!(
+            "HTTP/1.1 503 Service Unavailable\r\n\
+             Retry-After: {}\r\n\
+             Connection: close\r\n\
+             Content-Length: 0\r\n\
+             \r\n",
+            retry_after.as_secs().max(1)
+        );
+        stream.reject(response.as_bytes())
+    }
+
+    async fn process_single_stream(
+        &self,
+        stream: TcpStream,
+        guard: &ConnectionGuard,
+    ) -> Result<(), Error> {
+        #[cfg(feature = "tls")]
+        if let Some(acceptor) = &self.state.tls {
+            let stream = self.tls_handshake(acceptor, stream).await?;
+            let mut extensions = http::Extensions::new();
+            if let Some(peer) = tls::PeerCertificates::from_session(stream.get_ref().1) {
+                extensions.insert(peer);
+            }
+            return self
+                .serve_io(TarantoolAsyncIO::new(stream), extensions, guard)
+                .await;
+        }
+
+        self.serve_io(
+            TarantoolAsyncIO::new(stream),
+            http::Extensions::new(),
+            guard,
+        )
+        .await
+    }
+
+    /// Complete TLS handshake, giving up on shutdown or once [Timeouts::tls_handshake] is elapsed.
+    #[cfg(feature = "tls")]
+    async fn tls_handshake(
+        &self,
+        acceptor: &futures_rustls::TlsAcceptor,
+        stream: TcpStream,
+    ) -> Result<futures_rustls::server::TlsStream<TcpStream>, Error> {
+        let lifecycle = &self.state.lifecycle;
+        let mut handshake = pin!(acceptor.accept(stream));
+        let mut deadline = self.state.timeouts.tls_handshake.map(TarantoolSleep::new);
+        // No handlers are run yet, so shutdown wakes this fiber up right away.
+        poll_fn(|cx| {
+            if lifecycle.is_shutting_down() {
+                return Poll::Ready(Err(Error::ConnectionAborted));
+            }
+            if let Poll::Ready(result) = handshake.as_mut().poll(cx) {
+                return Poll::Ready(result.map_err(|err| {
+                    Error::ConnectionError(format!("tls handshake failed: {err}"))
+                }));
+            }
+            let timed_out = deadline
+                .as_mut()
+                .is_some_and(|deadline| Pin::new(deadline).poll(cx).is_ready());
+            if timed_out {
+                return Poll::Ready(Err(Error::ConnectionError(
+                    "tls handshake timed out".into(),
+                )));
+            }
+            Poll::Pending
+        })
+        .await
+    }
+
+    /// Serve HTTP over established connection.
+    ///
+    /// `extensions` describe the connection, they are copied into every request served over it.
+    async fn serve_io<S>(
+        &self,
+        io: TarantoolAsyncIO<S>,
+        extensions: http::Extensions,
+        guard: &ConnectionGuard,
+    ) -> Result<(), Error>
+    where
+        S: AsyncRead + AsyncWrite + Unpin + 'static,
+    {
         let processor = self.clone();
+        let activity = Rc::new(ConnectionActivity::new());
 
-        let service = service_fn(move |request| {
+        let service_activity = activity.clone();
+        let service = service_fn(move |mut request: HyperRequest<Incoming>| {
             trace!(ctx = processor.log_ctx(); "accepted request: {request:?}");
+            request.extensions_mut().extend(extensions.clone());
             let processor = processor.clone();
+            let in_progress = ConnectionActivity::enter_request(&service_activity);
             async move {
-                processor
-                    .process_request(request)
-                    .await
-                    .or_else(|err| processor.handle_error(err))
+                let _in_progress = in_progress;
+                let request = Request {
+                    content: request.map(|body| RequestBody::new(body, processor.state.body_limit)),
+                    params: HashMap::new(),
+                    states: processor.state.states.clone(),
+                };
+                Ok::<_, Error>((processor.state.handler.0).handle_async(request).await)
             }
         });
 
-        hyper_util::server::conn::auto::Builder::new(TarantoolHyperExecutor::new(
-            &self.state.server_name,
-        ))
-        .serve_connection(io, service)
-        .await
-        .map_err(|err| {
-            Error::ServeExited(format!(
-                "serve process resulted in error: {}",
-                error_with_causes(err)
-            ))
-        })?;
+        let timeouts = &self.state.timeouts;
+        let mut connection = pin!(self.state.builder.serve_connection(io, service));
+        let lifecycle = &self.state.lifecycle;
+        let mut graceful_requested = false;
+        // Shutdown wakes this fiber up, so the flags are rechecked even if
+        // the connection is waiting on the socket.
+        poll_fn(|cx| loop {
+            if lifecycle.is_aborted() {
+                return Poll::Ready(Err(Error::ConnectionAborted));
+            }
+            if lifecycle.is_shutting_down() && !graceful_requested {
+                graceful_requested = true;
+                connection.as_mut().graceful_shutdown();
+            }
+            let poll = guard.poll(|| connection.as_mut().poll(cx)).map_err(|err| {
+                Error::ServeExited(format!(
+                    "serve process resulted in error: {}",
+                    error_with_causes(err)
+                ))
+            });
+            if poll.is_ready() {
+                return poll;
+            }
+            // Shutdown doesn't wake up the connection being polled, so it's handled here.
+            if lifecycle.is_aborted() || (lifecycle.is_shutting_down() && !graceful_requested) {
+                continue;
+            }
+            if graceful_requested {
+                return poll;
+            }
+            let (Some(timeout), Some(idle_since)) = (timeouts.keep_alive, activity.idle_since())
+            else {
+                return poll;
+            };
+            let mut idle = TarantoolSleep::until(idle_since.saturating_add(timeout));
+            if Pin::new(&mut idle).poll(cx).is_pending() {
+                return poll;
+            }
+            debug!(ctx = self.log_ctx(); "closing idle connection");
+            graceful_requested = true;
+            connection.as_mut().graceful_shutdown();
+        })
+        .await?;
         debug!(ctx = self.log_ctx(); "connection is finished");
         Ok(())
     }
 
-    async fn process_request(&self, request: HyperRequest<Incoming>) -> Result<Response, Error> {
-        let bucket = self
-            .state
-            .router
-            .at(request.uri().path())
-            .map_err(|_| Error::NotFound)?;
+    fn log_ctx(&self) -> &str {
+        &self.state.server_name
+    }
+}
+
+/// Routes requests to the registered handlers.
+///
+/// Innermost handler of the server, wrapped by the server-wide middlewares,
+/// so they observe routing failures as well.
+struct Dispatcher {
+    router: InnerRouter,
+    fallbacks: Vec<Fallback>,
+    method_not_allowed: Option<SharedRequestHandler>,
+    pre_routing: Vec<PreRoutingHook>,
+    path_normalization: PathNormalization,
+    body_limit: BodyLimit,
+    server_name: String,
+}
 
-        let handler = bucket
-            .value
-            .get(request.method())
-            .ok_or(Error::MethodNotAllowed)?;
+#[async_trait::async_trait(?Send)]
+impl RequestHandler for Dispatcher {
+    async fn handle_async(&self, request: Request) -> Response {
+        match self.process_request(request).await {
+            Ok(response) => response,
+            Err(err) => self.handle_error(err).await,
+        }
+    }
+}
 
-        let params: HashMap<String, String> = bucket
+impl Dispatcher {
+    async fn process_request(&self, mut request: Request) -> Result<Response, Error> {
+        let original = request.uri().clone();
+        for hook in &self.pre_routing {
+            hook(&mut request);
+        }
+        if let Some(redirect) = self.normalize_path(&mut request, &original)? {
+            return Ok(redirect);
+        }
+
+        let Ok(bucket) = self.router.at(request.uri().path()) else {
+            return self.process_fallback(request).await;
+        };
+
+        let endpoints = bucket.value;
+        let params = bucket
             .params
             .iter()
-            .map(|(k, v)| (k.to_string(), v.to_string()))
-            .collect();
+            .map(|(name, value)| {
+                percent_decode_str(value)
+                    .decode_utf8()
+                    .map(|value| (name.to_string(), value.into_owned()))
+                    .map_err(|_| Error::InvalidPathParam {
+                        name: name.to_string(),
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        request.params = params.iter().cloned().collect();
+        request.extensions_mut().insert(PathParams(params));
+
+        let method = request.method();
+        let (endpoint, is_head) = match endpoints.get(method) {
+            Some(endpoint) => (endpoint, false),
+            None => match endpoints.get(&http::Method::GET) {
+                Some(endpoint) if method == http::Method::HEAD => (endpoint, true),
+                _ => {
+                    let allowed = AllowedMethods::new(endpoints.keys());
+                    if method == http::Method::OPTIONS {
+                        return Ok(methods::options_response(&allowed));
+                    }
+                    return self.process_method_not_allowed(request, allowed).await;
+                }
+            },
+        };
+
+        limit_body(&mut request, endpoint.body_limit.unwrap_or(self.body_limit))?;
+        let mut response = (endpoint.handler.0).handle_async(request).await;
+        if is_head {
+            methods::strip_body(&mut response);
+        }
+        Ok(response)
+    }
+
+    /// Rewrite the request path into the normalized one.
+    ///
+    /// Returns the redirect response instead, if the client should use the normalized path.
+    /// Redirect points to the `original` URI, as requested by the client before the
+    /// pre-routing hooks, with the same normalization applied.
+    fn normalize_path(
+        &self,
+        request: &mut Request,
+        original: &http::Uri,
+    ) -> Result<Option<Response>, Error> {
+        let normalization = &self.path_normalization;
+        let path = request.uri().path();
+        let mut target = normalization.normalize(path);
+        let mut routed = false;
+        let mut toggled = false;
+        if normalization.trailing_slash != TrailingSlash::Strict {
+            routed = self.router.at(&target).is_ok();
+            if !routed {
+                if let Some(alternative) = path::toggle_trailing_slash(&target)
+                    .filter(|alternative| self.router.at(alternative).is_ok())
+                {
+                    target = alternative.into();
+                    routed = true;
+                    toggled = true;
+                }
+            }
+        }
+        if target == path {
+            return Ok(None);
+        }
 
-        Ok((handler.0)
-            .handle_async(Request {
-                content: request,
-                params,
+        let target = target.into_owned();
+        let map_err =
+            |err: http::Error| Error::ConnectionError(format!("failed to normalize path: {err}"));
+        let redirect = (routed && normalization.trailing_slash == TrailingSlash::Redirect)
+            .then(|| {
+                let mut location = normalization.normalize(original.path()).into_owned();
+                if toggled {
+                    location = path::toggle_trailing_slash(&location).unwrap_or(location);
+                }
+                location
             })
-            .await)
+            // Hooks may have rewritten the path so that only the rewritten one needs
+            // normalization, it's served as is then.
+            .filter(|location| location != original.path());
+        if let Some(location) = redirect {
+            // Location starting with `//` would send the client to another host,
+            // so leading slashes are collapsed even if merging is disabled.
+            let location = format!("/{}", location.trim_start_matches('/'));
+            let location = match original.query() {
+                Some(query) => format!("{location}?{query}"),
+                None => location,
+            };
+            let location =
+                http::HeaderValue::try_from(location).map_err(|err| map_err(err.into()))?;
+            let mut response = Response::new(Body::empty());
+            *response.status_mut() = StatusCode::PERMANENT_REDIRECT;
+            response
+                .headers_mut()
+                .insert(http::header::LOCATION, location);
+            return Ok(Some(response));
+        }
+        request.set_path(&target).map_err(map_err)?;
+        Ok(None)
+    }
+
+    /// Pass unmatched request to the fallback with the longest matching prefix.
+    async fn process_fallback(&self, mut request: Request) -> Result<Response, Error> {
+        let handler = self
+            .fallbacks
+            .iter()
+            .filter(|fallback| fallback.matches(request.uri().path()))
+            .max_by_key(|fallback| fallback.prefix.len())
+            .map(|fallback| &fallback.handler)
+            .ok_or(Error::NotFound)?;
+
+        limit_body(&mut request, self.body_limit)?;
+        Ok((handler.0).handle_async(request).await)
     }
 
-    fn handle_error(&self, error: Error) -> Result<Response, Error> {
-        match error {
-            err @ Error::NotFound => {
-                let mut response = Response::new(err.to_string().into());
-                *response.status_mut() = StatusCode::NOT_FOUND;
-                Ok(response)
+    /// Answer the request to the known path with unsupported method.
+    async fn process_method_not_allowed(
+        &self,
+        mut request: Request,
+        allowed: AllowedMethods,
+    ) -> Result<Response, Error> {
+        let mut response = match &self.method_not_allowed {
+            Some(handler) => {
+                limit_body(&mut request, self.body_limit)?;
+                request.extensions_mut().insert(allowed.clone());
+                (handler.0).handle_async(request).await
             }
-            err @ Error::MethodNotAllowed => {
-                let mut response = Response::new(err.to_string().into());
-                *response.status_mut() = StatusCode::METHOD_NOT_ALLOWED;
-                Ok(response)
+            None => self.handle_error(Error::MethodNotAllowed).await,
+        };
+        allowed.apply(&mut response);
+        Ok(response)
+    }
+
+    async fn handle_error(&self, error: Error) -> Response {
+        // Rejected requests are answered like the extractors do, so they are rendered
+        // by the rejection handler as well.
+        #[cfg(feature = "frontend")]
+        if let Some(rejection) = error_rejection(&error) {
+            use crate::frontend::response::ResponsePart as _;
+            let mut response = Response::default();
+            rejection.apply(&mut response).await;
+            return response;
+        }
+        let status = match error {
+            Error::NotFound => StatusCode::NOT_FOUND,
+            Error::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+            Error::PayloadTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            Error::InvalidPathParam { .. } => StatusCode::BAD_REQUEST,
+            ref err => {
+                error!(ctx = self.server_name.as_str(); "failed to process request: {err}");
+                StatusCode::INTERNAL_SERVER_ERROR
             }
-            e => Err(e),
+        };
+        let mut response = Response::new(error.to_string().into());
+        *response.status_mut() = status;
+        response
+    }
+}
+
+/// Rejection answering the request failed by the dispatcher, if it's the client fault.
+#[cfg(feature = "frontend")]
+fn error_rejection(error: &Error) -> Option<crate::frontend::request::rejection::Rejection> {
+    use crate::frontend::request::rejection::Rejection;
+    match error {
+        Error::PayloadTooLarge { limit } => Some(BodyError::LengthLimit { limit: *limit }.into()),
+        Error::InvalidPathParam { name } => Some(Rejection::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_path_params",
+            format!("path parameter `{name}` is not valid UTF-8"),
+        )),
+        _ => None,
+    }
+}
+
+/// Apply the route body limit to the request.
+///
+/// Declared length is checked right away, so the body is not even started to be read.
+fn limit_body(request: &mut Request, limit: BodyLimit) -> Result<(), Error> {
+    let content_length = request
+        .headers()
+        .get(http::header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok()?.parse::<u64>().ok());
+    if let BodyLimit::Bytes(bytes) = limit {
+        if content_length.is_some_and(|length| limit.is_exceeded_by(length)) {
+            return Err(Error::PayloadTooLarge { limit: bytes });
         }
     }
+    request.body_mut().set_limit(limit);
+    Ok(())
+}
 
-    fn log_ctx(&self) -> &str {
-        &self.state.server_name
+/// Build the connection builder shared by all of the server connections.
+fn connection_builder(
+    cfg: &ServerConfig,
+    server_name: &str,
+) -> Result<auto::Builder<TarantoolHyperExecutor>, Error> {
+    cfg.http1.validate()?;
+    cfg.http2.validate()?;
+
+    let mut builder = auto::Builder::new(TarantoolHyperExecutor::new(server_name));
+    builder
+        .http1()
+        .timer(TarantoolHyperTimer)
+        .header_read_timeout(cfg.timeouts.header_read);
+    builder
+        .http2()
+        .timer(TarantoolHyperTimer)
+        .keep_alive_interval(cfg.timeouts.http2_ping_interval)
+        .keep_alive_timeout(cfg.timeouts.http2_ping_timeout);
+    cfg.http1.apply(&mut builder);
+    cfg.http2.apply(&mut builder);
+    Ok(cfg.protocol.apply(builder))
+}
+
+/// Tracks requests in progress on a single connection, used to detect idle connections.
+struct ConnectionActivity {
+    in_progress: Cell<usize>,
+    last_active: Cell<tarantool::time::Instant>,
+}
+
+impl ConnectionActivity {
+    fn new() -> Self {
+        Self {
+            in_progress: Cell::new(0),
+            last_active: Cell::new(fiber::clock()),
+        }
+    }
+
+    /// Connection is considered busy until returned guard is dropped.
+    fn enter_request(self: &Rc<Self>) -> RequestGuard {
+        self.in_progress.set(self.in_progress.get() + 1);
+        RequestGuard(self.clone())
+    }
+
+    /// Instant since which there are no requests in progress.
+    fn idle_since(&self) -> Option<tarantool::time::Instant> {
+        (self.in_progress.get() == 0).then(|| self.last_active.get())
+    }
+}
+
+struct RequestGuard(Rc<ConnectionActivity>);
+
+impl Drop for RequestGuard {
+    fn drop(&mut self) {
+        self.0.in_progress.set(self.0.in_progress.get() - 1);
+        self.0.last_active.set(fiber::clock());
     }
 }
 
 struct ServerState {
-    router: InnerRouter,
+    /// Request dispatcher wrapped with the server middlewares.
+    handler: SharedRequestHandler,
     server_name: String,
+    lifecycle: Rc<Lifecycle>,
+    accept_policy: AcceptPolicy,
+    /// Connection builder with protocol settings applied.
+    builder: auto::Builder<TarantoolHyperExecutor>,
+    timeouts: Timeouts,
+    connection_limit: Option<ConnectionLimit>,
+    body_limit: BodyLimit,
+    /// Server-wide states, passed to every request.
+    states: RequestStates,
+    #[cfg(feature = "tls")]
+    tls: Option<futures_rustls::TlsAcceptor>,
 }
 
 pub struct Request {
-    pub content: HyperRequest<Incoming>,
+    pub content: HyperRequest<RequestBody>,
+    /// Percent-decoded path parameters of the matched route.
     pub params: HashMap<String, String>,
+    /// Read by [crate::frontend::request::state::State] only.
+    #[cfg_attr(not(feature = "frontend"), allow(dead_code))]
+    pub(crate) states: RequestStates,
+}
+
+/// Percent-decoded path parameters of the matched route, in the order they appear in the path.
+///
+/// Passed in request extensions, so parameters could be deserialized into tuples.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathParams(Vec<(String, String)>);
+
+impl PathParams {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl FromIterator<(String, String)> for PathParams {
+    fn from_iter<I: IntoIterator<Item = (String, String)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
 }
 
 impl Deref for Request {
-    type Target = HyperRequest<Incoming>;
+    type Target = HyperRequest<RequestBody>;
 
     fn deref(&self) -> &Self::Target {
         &self.content
     }
 }
 
+impl Request {
+    /// Replace the path of the request URI, keeping its query.
+    ///
+    /// Useful for rewriting the request in the [Server::pre_routing] hooks.
+    pub fn set_path(&mut self, path: &str) -> Result<(), http::Error> {
+        let path_and_query = match self.uri().query() {
+            Some(query) => format!("{path}?{query}"),
+            None => path.to_string(),
+        };
+        let mut parts = self.uri().clone().into_parts();
+        parts.path_and_query = Some(path_and_query.try_into()?);
+        *self.uri_mut() = http::Uri::from_parts(parts)?;
+        Ok(())
+    }
+}
+
 impl DerefMut for Request {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.content
@@ -392,6 +1318,11 @@ pub type Response = HyperResponse<Body>;
 #[async_trait::async_trait(?Send)]
 pub trait RequestHandler {
     async fn handle_async(&self, request: Request) -> Response;
+
+    /// States the handler extracts, checked to be registered before the server starts.
+    fn required_states(&self) -> Vec<StateType> {
+        Vec::new()
+    }
 }
 
 #[async_trait::async_trait(?Send)]
@@ -477,6 +1408,14 @@ pub enum Error {
     NotFound,
     #[error("405 Method Not Allowed")]
     MethodNotAllowed,
+    #[error("413 Payload Too Large: request body exceeds the limit of {limit} bytes")]
+    PayloadTooLarge { limit: usize },
+    #[error("400 Bad Request: path parameter `{name}` is not valid UTF-8")]
+    InvalidPathParam { name: String },
+    #[error("connection is aborted due to server shutdown")]
+    ConnectionAborted,
+    #[error("server shutdown deadline exceeded, {0} connections were aborted")]
+    ShutdownTimeout(usize),
     #[error("invalid path, unable to register: {path}: {error}")]
     InvalidPath {
         path: String,
@@ -485,6 +1424,11 @@ pub enum Error {
     },
     #[error("route {path} is already occupied for method {method}")]
     RouteOccupied { path: String, method: http::Method },
+    #[error("state `{state}` required by {required_by} is not registered")]
+    MissingState {
+        state: &'static str,
+        required_by: String,
+    },
 }
 
 /// Format error as a string with sources traversal.
@@ -0,0 +1,90 @@
+//! Normalization of the request path before routing.
+use std::borrow::Cow;
+
+use derive_builder::Builder;
+
+/// How the paths differing from the route only by the trailing slash are treated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrailingSlash {
+    /// `/users` and `/users/` are different paths.
+    #[default]
+    Strict,
+    /// Path is routed as if it was written the way the route is registered.
+    MatchBoth,
+    /// Client is redirected with `308 Permanent Redirect` to the path the route is registered with.
+    Redirect,
+}
+
+/// Normalization applied to the request path before routing.
+///
+/// Unmatched requests are passed to the fallbacks with the normalized path.
+#[derive(Debug, Clone, Builder)]
+#[builder(default)]
+pub struct PathNormalization {
+    pub trailing_slash: TrailingSlash,
+    /// Collapse consecutive slashes, i.e. `/users//1` into `/users/1`.
+    pub merge_slashes: bool,
+    /// Resolve `.` and `..` segments, i.e. `/users/./1/../2` into `/users/2`.
+    pub resolve_dot_segments: bool,
+}
+
+impl Default for PathNormalization {
+    fn default() -> Self {
+        Self {
+            trailing_slash: TrailingSlash::default(),
+            merge_slashes: true,
+            resolve_dot_segments: true,
+        }
+    }
+}
+
+impl PathNormalization {
+    /// Collapse slashes and resolve dot segments, according to the settings.
+    pub(crate) fn normalize<'a>(&self, path: &'a str) -> Cow<'a, str> {
+        // Asterisk form of `OPTIONS` and alike are left as is.
+        if !path.starts_with('/') {
+            return Cow::Borrowed(path);
+        }
+
+        let mut segments = Vec::new();
+        let mut trailing_slash = false;
+        for segment in path.split('/').skip(1) {
+            trailing_slash = true;
+            match segment {
+                "" if self.merge_slashes => {}
+                "." if self.resolve_dot_segments => {}
+                ".." if self.resolve_dot_segments => {
+                    segments.pop();
+                }
+                segment => {
+                    trailing_slash = false;
+                    segments.push(segment);
+                }
+            }
+        }
+
+        let mut normalized = String::with_capacity(path.len());
+        for segment in &segments {
+            normalized.push('/');
+            normalized.push_str(segment);
+        }
+        if trailing_slash || segments.is_empty() {
+            normalized.push('/');
+        }
+
+        if normalized == path {
+            Cow::Borrowed(path)
+        } else {
+            Cow::Owned(normalized)
+        }
+    }
+}
+
+/// Same path with the trailing slash added or removed, `None` for the root.
+pub(crate) fn toggle_trailing_slash(path: &str) -> Option<String> {
+    match path.strip_suffix('/') {
+        Some("") => None,
+        Some(stripped) => Some(stripped.to_string()),
+        None => Some(format!("{path}/")),
+    }
+}
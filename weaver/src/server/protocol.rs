@@ -0,0 +1,132 @@
+//! HTTP protocol versions served and their settings.
+use derive_builder::Builder;
+use hyper_util::server::conn::auto;
+
+use super::Error;
+
+/// HTTP versions served by the server.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Protocol {
+    /// Version is detected per connection: ALPN for TLS connections,
+    /// HTTP/2 connection preface for cleartext ones (h2c with prior knowledge).
+    #[default]
+    Auto,
+    /// HTTP/1.x only.
+    Http1,
+    /// HTTP/2 only, cleartext connections must start with the HTTP/2 preface.
+    Http2,
+}
+
+impl Protocol {
+    pub(crate) fn apply<E>(self, builder: auto::Builder<E>) -> auto::Builder<E> {
+        match self {
+            Self::Auto => builder,
+            Self::Http1 => builder.http1_only(),
+            Self::Http2 => builder.http2_only(),
+        }
+    }
+
+    /// Whether the protocol identified through ALPN could be served.
+    #[cfg(feature = "tls")]
+    pub(crate) fn supports_alpn(self, alpn: &[u8]) -> bool {
+        match self {
+            Self::Auto => true,
+            Self::Http1 => alpn != super::tls::ALPN_H2,
+            Self::Http2 => alpn == super::tls::ALPN_H2,
+        }
+    }
+}
+
+/// Settings of HTTP/1 connections. Unset values fall back to hyper defaults.
+#[derive(Debug, Clone, Default, Builder)]
+#[builder(default)]
+pub struct Http1Config {
+    /// Write response header names in Title-Case, for clients which can't handle lowercase ones.
+    pub title_case_headers: bool,
+    /// Keep serving the connection after the client has shut down its write side.
+    pub half_close: bool,
+    /// Maximum size of the connection read and write buffers, at least 8192 bytes.
+    pub max_buf_size: Option<usize>,
+}
+
+/// Minimum buffer size allowed by hyper.
+const MIN_HTTP1_BUF_SIZE: usize = 8192;
+
+impl Http1Config {
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        match self.max_buf_size {
+            Some(size) if size < MIN_HTTP1_BUF_SIZE => Err(Error::InitFailed(format!(
+                "http1 max buffer size must be at least {MIN_HTTP1_BUF_SIZE}, got {size}"
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn apply<E>(&self, builder: &mut auto::Builder<E>) {
+        let mut http1 = builder.http1();
+        http1
+            .title_case_headers(self.title_case_headers)
+            .half_close(self.half_close);
+        if let Some(size) = self.max_buf_size {
+            http1.max_buf_size(size);
+        }
+    }
+}
+
+/// Settings of HTTP/2 connections. Unset values fall back to hyper defaults.
+#[derive(Debug, Clone, Default, Builder)]
+#[builder(default)]
+pub struct Http2Config {
+    /// Maximum amount of concurrent streams the client may open on a connection.
+    pub max_concurrent_streams: Option<u32>,
+    /// Initial flow control window of a stream, up to `2^31 - 1` bytes.
+    pub initial_stream_window_size: Option<u32>,
+    /// Initial flow control window of a connection, up to `2^31 - 1` bytes.
+    pub initial_connection_window_size: Option<u32>,
+    /// Maximum frame payload the server accepts, between `2^14` and `2^24 - 1` bytes.
+    pub max_frame_size: Option<u32>,
+    /// Maximum size of the decoded request header list.
+    pub max_header_list_size: Option<u32>,
+}
+
+const MAX_HTTP2_WINDOW_SIZE: u32 = (1 << 31) - 1;
+const MIN_HTTP2_FRAME_SIZE: u32 = 1 << 14;
+const MAX_HTTP2_FRAME_SIZE: u32 = (1 << 24) - 1;
+
+impl Http2Config {
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        for (name, size) in [
+            ("stream", self.initial_stream_window_size),
+            ("connection", self.initial_connection_window_size),
+        ] {
+            if let Some(size) = size.filter(|size| *size > MAX_HTTP2_WINDOW_SIZE) {
+                return Err(Error::InitFailed(format!(
+                    "http2 initial {name} window size must be at most {MAX_HTTP2_WINDOW_SIZE}, got {size}"
+                )));
+            }
+        }
+        match self.max_frame_size {
+            Some(size) if !(MIN_HTTP2_FRAME_SIZE..=MAX_HTTP2_FRAME_SIZE).contains(&size) => {
+                Err(Error::InitFailed(format!(
+                    "http2 max frame size must be between {MIN_HTTP2_FRAME_SIZE} and {MAX_HTTP2_FRAME_SIZE}, got {size}"
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn apply<E>(&self, builder: &mut auto::Builder<E>) {
+        let mut http2 = builder.http2();
+        http2
+            .initial_stream_window_size(self.initial_stream_window_size)
+            .initial_connection_window_size(self.initial_connection_window_size)
+            .max_frame_size(self.max_frame_size);
+        // Unlike the rest of the settings, `None` would lift the hyper default limit.
+        if let Some(max) = self.max_concurrent_streams {
+            http2.max_concurrent_streams(max);
+        }
+        if let Some(size) = self.max_header_list_size {
+            http2.max_header_list_size(size);
+        }
+    }
+}
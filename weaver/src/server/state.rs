@@ -0,0 +1,68 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    rc::Rc,
+};
+
+/// Type of the shared state handlers require, see [super::Server::with_state].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StateType {
+    id: TypeId,
+    name: &'static str,
+}
+
+impl StateType {
+    pub fn of<T: 'static>() -> Self {
+        Self {
+            id: TypeId::of::<T>(),
+            name: std::any::type_name::<T>(),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+/// Shared state values by their type.
+///
+/// Values are held in [Rc], so the map is cheap to clone and is shared by all the requests.
+#[derive(Clone, Default)]
+pub struct StateMap(Rc<HashMap<TypeId, Rc<dyn Any>>>);
+
+impl StateMap {
+    /// Insert the value, replacing the previous one of the same type.
+    pub fn insert<T: 'static>(&mut self, value: T) {
+        Rc::make_mut(&mut self.0).insert(TypeId::of::<T>(), Rc::new(value));
+    }
+
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.0
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref())
+    }
+
+    pub fn contains(&self, state: StateType) -> bool {
+        self.0.contains_key(&state.id)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// State maps available to the request, from the server-wide to the innermost group ones.
+#[derive(Clone, Default)]
+pub(crate) struct RequestStates(Vec<StateMap>);
+
+impl RequestStates {
+    pub(crate) fn push(&mut self, states: StateMap) {
+        self.0.push(states);
+    }
+
+    /// Value of the innermost map containing it.
+    #[cfg(feature = "frontend")]
+    pub(crate) fn get<T: 'static>(&self) -> Option<&T> {
+        self.0.iter().rev().find_map(StateMap::get)
+    }
+}
@@ -0,0 +1,185 @@
+//! TLS termination for the server connections.
+use std::{path::PathBuf, sync::Arc};
+
+use derive_builder::Builder;
+use futures_rustls::{
+    rustls::{
+        self,
+        pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer},
+        server::WebPkiClientVerifier,
+        RootCertStore,
+    },
+    TlsAcceptor,
+};
+
+use super::{Error, Protocol};
+
+/// Source of PEM-encoded data.
+#[derive(Debug, Clone)]
+pub enum Pem {
+    /// Path to the PEM file, read once the server is started.
+    File(PathBuf),
+    /// PEM content itself.
+    Bytes(Vec<u8>),
+}
+
+impl Pem {
+    pub fn file(path: impl Into<PathBuf>) -> Self {
+        Self::File(path.into())
+    }
+
+    pub fn bytes(content: impl Into<Vec<u8>>) -> Self {
+        Self::Bytes(content.into())
+    }
+
+    fn certificates(&self) -> Result<Vec<CertificateDer<'static>>, rustls::pki_types::pem::Error> {
+        match self {
+            Self::File(path) => CertificateDer::pem_file_iter(path)?.collect(),
+            Self::Bytes(bytes) => CertificateDer::pem_slice_iter(bytes).collect(),
+        }
+    }
+
+    fn private_key(&self) -> Result<PrivateKeyDer<'static>, rustls::pki_types::pem::Error> {
+        match self {
+            Self::File(path) => PrivateKeyDer::from_pem_file(path),
+            Self::Bytes(bytes) => PrivateKeyDer::from_pem_slice(bytes),
+        }
+    }
+}
+
+/// ALPN protocol identifier for HTTP/2.
+pub const ALPN_H2: &[u8] = b"h2";
+/// ALPN protocol identifier for HTTP/1.1.
+pub const ALPN_HTTP11: &[u8] = b"http/1.1";
+
+/// TLS settings of the server.
+///
+/// When set, every accepted connection is wrapped into the TLS session before
+/// HTTP is served on top of it.
+#[derive(Debug, Clone, Builder)]
+pub struct TlsConfig {
+    /// Server certificate chain, leaf certificate goes first.
+    pub certificate_chain: Pem,
+    /// Private key of the leaf certificate: PKCS#1, PKCS#8 or SEC1 encoded.
+    pub private_key: Pem,
+    /// Protocols advertised through ALPN, in the order of preference.
+    /// Both HTTP/2 and HTTP/1.1 are advertised by default.
+    #[builder(default = "vec![ALPN_H2.to_vec(), ALPN_HTTP11.to_vec()]")]
+    pub alpn_protocols: Vec<Vec<u8>>,
+    /// Verification of client certificates, disabled by default.
+    #[builder(default)]
+    pub client_auth: Option<ClientAuth>,
+}
+
+/// Client certificates verification settings (mutual TLS).
+///
+/// Verified certificate chain is available to handlers through [PeerCertificates]
+/// request extension.
+#[derive(Debug, Clone)]
+pub struct ClientAuth {
+    /// Bundle of trusted CA certificates client certificates are verified against.
+    pub ca_certificates: Pem,
+    /// Whether clients without certificate are rejected during handshake.
+    /// If `false`, such clients are served without [PeerCertificates].
+    pub required: bool,
+}
+
+impl ClientAuth {
+    /// Reject clients without valid certificate.
+    pub fn required(ca_certificates: Pem) -> Self {
+        Self {
+            ca_certificates,
+            required: true,
+        }
+    }
+
+    /// Verify client certificate if it's provided, but allow anonymous clients as well.
+    pub fn optional(ca_certificates: Pem) -> Self {
+        Self {
+            ca_certificates,
+            required: false,
+        }
+    }
+}
+
+/// Certificate chain presented by the client, leaf certificate goes first.
+///
+/// Inserted into request extensions of every request served over mutual TLS connection.
+#[derive(Debug, Clone)]
+pub struct PeerCertificates(pub Arc<[CertificateDer<'static>]>);
+
+impl PeerCertificates {
+    /// Extract certificates of the peer from established session.
+    pub(crate) fn from_session(session: &rustls::ServerConnection) -> Option<Self> {
+        session
+            .peer_certificates()
+            .filter(|chain| !chain.is_empty())
+            .map(|chain| Self(chain.iter().map(|cert| cert.clone().into_owned()).collect()))
+    }
+
+    pub fn leaf(&self) -> &CertificateDer<'static> {
+        &self.0[0]
+    }
+}
+
+impl TlsConfig {
+    /// Load certificates and build the acceptor for incoming connections.
+    ///
+    /// Only ALPN protocols which could be served with `protocol` are advertised.
+    pub(crate) fn acceptor(&self, protocol: Protocol) -> Result<TlsAcceptor, Error> {
+        let certificates = self.certificate_chain.certificates().map_err(|err| {
+            Error::InitFailed(format!("failed to load tls certificate chain: {err}"))
+        })?;
+        let private_key = self
+            .private_key
+            .private_key()
+            .map_err(|err| Error::InitFailed(format!("failed to load tls private key: {err}")))?;
+
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let builder = rustls::ServerConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()
+            .map_err(|err| Error::InitFailed(format!("invalid tls configuration: {err}")))?;
+        let builder = match &self.client_auth {
+            Some(client_auth) => builder.with_client_cert_verifier(client_auth.verifier(provider)?),
+            None => builder.with_no_client_auth(),
+        };
+        let mut config = builder
+            .with_single_cert(certificates, private_key)
+            .map_err(|err| Error::InitFailed(format!("invalid tls configuration: {err}")))?;
+        config.alpn_protocols = self
+            .alpn_protocols
+            .iter()
+            .filter(|alpn| protocol.supports_alpn(alpn))
+            .cloned()
+            .collect();
+
+        Ok(TlsAcceptor::from(Arc::new(config)))
+    }
+}
+
+impl ClientAuth {
+    fn verifier(
+        &self,
+        provider: Arc<rustls::crypto::CryptoProvider>,
+    ) -> Result<Arc<dyn rustls::server::danger::ClientCertVerifier>, Error> {
+        let mut roots = RootCertStore::empty();
+        let certificates = self.ca_certificates.certificates().map_err(|err| {
+            Error::InitFailed(format!("failed to load client ca certificates: {err}"))
+        })?;
+        for certificate in certificates {
+            roots.add(certificate).map_err(|err| {
+                Error::InitFailed(format!("invalid client ca certificate: {err}"))
+            })?;
+        }
+
+        let builder = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider);
+        let builder = if self.required {
+            builder
+        } else {
+            builder.allow_unauthenticated()
+        };
+        builder
+            .build()
+            .map_err(|err| Error::InitFailed(format!("invalid client auth configuration: {err}")))
+    }
+}
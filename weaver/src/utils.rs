@@ -33,4 +33,8 @@ impl<K: Eq, V, const N: usize> SmallMap<K, V, N> {
             .find(|(k, _)| k == key)
             .map(|(_, value)| value)
     }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.inner.iter().map(|(k, _)| k)
+    }
 }